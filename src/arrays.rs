@@ -1,18 +1,34 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, cmp::Ordering, fmt, rc::Rc};
 
-pub fn binary_search(arr: &[i64], target: i64, mut low: usize, mut high: usize) -> Option<usize> {
+pub fn binary_search<T: Ord>(arr: &[T], target: &T) -> Option<usize> {
+    binary_search_by(arr, |val| val.cmp(target))
+}
+
+/// Searches a slice sorted in the order `cmp` describes. `cmp` compares an
+/// element with the one searched for, like `slice::binary_search_by`.
+pub fn binary_search_by<T, F>(arr: &[T], mut cmp: F) -> Option<usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let (mut low, mut high) = (0, arr.len());
     while low < high {
-        let mid_idx = (low + (high - low) / 2) as usize;
-        let mid_val = arr[mid_idx];
-        if mid_val == target {
-            return Some(mid_idx);
-        } else if mid_val > target {
-            high = mid_idx;
-        } else {
-            low = mid_idx + 1;
+        let mid_idx = low + (high - low) / 2;
+        match cmp(&arr[mid_idx]) {
+            Ordering::Equal => return Some(mid_idx),
+            Ordering::Greater => high = mid_idx,
+            Ordering::Less => low = mid_idx + 1,
         }
     }
-    return None;
+    None
+}
+
+/// Searches a slice sorted by the key `key` extracts.
+pub fn binary_search_by_key<T, K, F>(arr: &[T], target: &K, mut key: F) -> Option<usize>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    binary_search_by(arr, |val| key(val).cmp(target))
 }
 
 pub fn two_crystal_balls(arr: &[bool]) -> Option<usize> {
@@ -36,42 +52,1440 @@ pub fn two_crystal_balls(arr: &[bool]) -> Option<usize> {
     return None;
 }
 
-pub fn bubble_sort(arr: &mut [i64]) {
+pub fn bubble_sort<T: Ord>(arr: &mut [T]) {
     for i in 0..arr.len() {
         let stop = arr.len() - i;
         for i in 0..stop - 1 {
             if arr[i] > arr[i + 1] {
-                let curr_val = arr[i];
-                arr[i] = arr[i + 1];
-                arr[i + 1] = curr_val;
+                arr.swap(i, i + 1);
             }
         }
     }
 }
 
-pub fn quicksort(arr: &mut [i64], low: i64, high: i64) {
-    if low >= high {
-        return;
+pub fn quicksort<T: Ord>(arr: &mut [T]) {
+    quicksort_by(arr, T::cmp);
+}
+
+/// Sorts with `cmp` deciding the order, like `slice::sort_unstable_by`.
+pub fn quicksort_by<T, F>(arr: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quicksort_slice(arr, &mut cmp);
+}
+
+/// Sorts by the key `key` extracts, which is recomputed on every comparison.
+pub fn quicksort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    quicksort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+/// Partitions below this length are insertion sorted.
+const INSERTION_SORT_LEN: usize = 16;
+/// Partitions from this length on pick their pivot with a ninther.
+const NINTHER_LEN: usize = 64;
+
+/// Introsort: quicksort that insertion sorts short partitions and hands
+/// over to heapsort once it recurses 2·log₂ n deep, so it stays O(n log n)
+/// on any input. Only the smaller side is recursed into and the larger one
+/// is looped on, so the stack depth is O(log n) as well.
+fn quicksort_slice<T, F>(mut arr: &mut [T], cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut depth_limit = 2 * (usize::BITS - arr.len().leading_zeros());
+    loop {
+        if arr.len() < INSERTION_SORT_LEN {
+            insertion_sort_by(arr, cmp);
+            return;
+        }
+        if depth_limit == 0 {
+            heapsort_by(arr, cmp);
+            return;
+        }
+        depth_limit -= 1;
+
+        let pivot_idx = choose_pivot(arr, cmp);
+        let last = arr.len() - 1;
+        arr.swap(pivot_idx, last);
+        let pivot_idx = partition_by(arr, cmp);
+        let (left, right) = arr.split_at_mut(pivot_idx);
+        let right = &mut right[1..];
+        if left.len() < right.len() {
+            quicksort_slice(left, cmp);
+            arr = right;
+        } else {
+            quicksort_slice(right, cmp);
+            arr = left;
+        }
+    }
+}
+
+/// The median of the first, middle and last elements, or for long slices
+/// the median of three such medians spread over the slice (a ninther).
+fn choose_pivot<T, F>(arr: &[T], cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let len = arr.len();
+    let (low, mid, high) = (0, len / 2, len - 1);
+    if len < NINTHER_LEN {
+        return median_of_three(arr, low, mid, high, cmp);
+    }
+    let step = len / 8;
+    let low = median_of_three(arr, low, low + step, low + 2 * step, cmp);
+    let mid = median_of_three(arr, mid - step, mid, mid + step, cmp);
+    let high = median_of_three(arr, high - 2 * step, high - step, high, cmp);
+    median_of_three(arr, low, mid, high, cmp)
+}
+
+fn median_of_three<T, F>(arr: &[T], a: usize, b: usize, c: usize, cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let less = |x: usize, y: usize, cmp: &mut F| cmp(&arr[x], &arr[y]) == Ordering::Less;
+    if less(a, b, cmp) {
+        if less(b, c, cmp) {
+            b
+        } else if less(a, c, cmp) {
+            c
+        } else {
+            a
+        }
+    } else if less(a, c, cmp) {
+        a
+    } else if less(b, c, cmp) {
+        c
+    } else {
+        b
     }
+}
 
-    let pivotIdx = partition(arr, low, high);
-    quicksort(arr, low, pivotIdx - 1);
-    quicksort(arr, pivotIdx + 1, high);
+fn insertion_sort_by<T, F>(arr: &mut [T], cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in 1..arr.len() {
+        let mut j = i;
+        while j > 0 && cmp(&arr[j - 1], &arr[j]) == Ordering::Greater {
+            arr.swap(j - 1, j);
+            j -= 1;
+        }
+    }
 }
 
-pub fn partition(arr: &mut [i64], low: i64, high: i64) -> i64 {
-    let pivot = arr[high as usize];
-    let mut switch = low - 1;
-    for i in low..=high {
-        if arr[i as usize] <= pivot {
+/// Partitions around the last element and returns where it ends up:
+/// everything before it is no greater, everything after it is greater.
+pub fn partition<T: Ord>(arr: &mut [T]) -> usize {
+    partition_by(arr, &mut T::cmp)
+}
+
+fn partition_by<T, F>(arr: &mut [T], cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if arr.is_empty() {
+        return 0;
+    }
+    let high = arr.len() - 1;
+    let mut switch = 0;
+    for i in 0..high {
+        if cmp(&arr[i], &arr[high]) != Ordering::Greater {
+            arr.swap(i, switch);
             switch += 1;
-            let temp = arr[i as usize];
-            arr[i as usize] = arr[switch as usize];
-            arr[switch as usize] = temp;
         }
     }
+    arr.swap(switch, high);
+    switch
+}
+
+/// Sorts in place by building a max-heap at the front of the slice and
+/// repeatedly swapping its root behind it. No allocation, O(n log n) always.
+pub fn heapsort<T: Ord>(arr: &mut [T]) {
+    heapsort_by(arr, &mut T::cmp);
+}
+
+fn heapsort_by<T, F>(arr: &mut [T], cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for idx in (0..arr.len() / 2).rev() {
+        sift_down(arr, idx, arr.len(), cmp);
+    }
+    for end in (1..arr.len()).rev() {
+        arr.swap(0, end);
+        sift_down(arr, 0, end, cmp);
+    }
+}
+
+fn sift_down<T, F>(arr: &mut [T], mut idx: usize, len: usize, cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    loop {
+        let left = idx * 2 + 1;
+        let right = left + 1;
+        if left >= len {
+            return;
+        }
+        let max = if right < len && cmp(&arr[right], &arr[left]) == Ordering::Greater {
+            right
+        } else {
+            left
+        };
+        if cmp(&arr[max], &arr[idx]) != Ordering::Greater {
+            return;
+        }
+        arr.swap(idx, max);
+        idx = max;
+    }
+}
+
+pub fn merge_sort<T: Ord + Clone>(arr: &mut [T]) {
+    merge_sort_by(arr, T::cmp);
+}
+
+/// A stable top-down merge sort. Halves that are already in order are not
+/// merged, and short runs are insertion sorted.
+pub fn merge_sort_by<T, F>(arr: &mut [T], mut cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut buf = Vec::with_capacity(arr.len() / 2);
+    merge_sort_slice(arr, &mut buf, &mut cmp);
+}
+
+fn merge_sort_slice<T, F>(arr: &mut [T], buf: &mut Vec<T>, cmp: &mut F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if arr.len() < INSERTION_SORT_LEN {
+        insertion_sort_by(arr, cmp);
+        return;
+    }
+    let mid = arr.len() / 2;
+    merge_sort_slice(&mut arr[..mid], buf, cmp);
+    merge_sort_slice(&mut arr[mid..], buf, cmp);
+    merge(arr, mid, buf, cmp);
+}
+
+/// A stable bottom-up merge sort: merges runs of 1, 2, 4, ... elements
+/// without recursing.
+pub fn merge_sort_bottom_up_by<T, F>(arr: &mut [T], mut cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut buf = Vec::with_capacity(arr.len() / 2);
+    let mut width = 1;
+    while width < arr.len() {
+        for start in (0..arr.len()).step_by(2 * width) {
+            let end = arr.len().min(start + 2 * width);
+            if start + width < end {
+                merge(&mut arr[start..end], width, &mut buf, &mut cmp);
+            }
+        }
+        width *= 2;
+    }
+}
+
+/// A stable merge sort that merges the runs already in the input, so it
+/// is O(n) on sorted or reversed input. Strictly descending runs are
+/// reversed first, which keeps equal elements in order.
+pub fn merge_sort_natural_by<T, F>(arr: &mut [T], mut cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut bounds = vec![0];
+    let mut start = 0;
+    while start < arr.len() {
+        let end = start + find_run(&mut arr[start..], &mut cmp);
+        bounds.push(end);
+        start = end;
+    }
+
+    let mut buf = Vec::with_capacity(arr.len() / 2);
+    while bounds.len() > 2 {
+        let mut merged = vec![0];
+        for pair in bounds[1..].chunks(2) {
+            let start = *merged.last().unwrap();
+            if let [mid, end] = *pair {
+                merge(&mut arr[start..end], mid - start, &mut buf, &mut cmp);
+            }
+            merged.push(*pair.last().unwrap());
+        }
+        bounds = merged;
+    }
+}
+
+/// The length of the run at the start of `arr`, after reversing it if it
+/// is strictly descending.
+fn find_run<T, F>(arr: &mut [T], cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if arr.len() < 2 {
+        return arr.len();
+    }
+    let mut end = 2;
+    if cmp(&arr[1], &arr[0]) == Ordering::Less {
+        while end < arr.len() && cmp(&arr[end], &arr[end - 1]) == Ordering::Less {
+            end += 1;
+        }
+        arr[..end].reverse();
+    } else {
+        while end < arr.len() && cmp(&arr[end], &arr[end - 1]) != Ordering::Less {
+            end += 1;
+        }
+    }
+    end
+}
+
+/// Merges the sorted `arr[..mid]` and `arr[mid..]`, taking from the left on
+/// ties. The left half is cloned into `buf`; after that elements are only
+/// swapped, into the gap that opens up behind the merge.
+fn merge<T, F>(arr: &mut [T], mid: usize, buf: &mut Vec<T>, cmp: &mut F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if mid == 0 || mid == arr.len() || cmp(&arr[mid], &arr[mid - 1]) != Ordering::Less {
+        return;
+    }
+    buf.clear();
+    buf.extend_from_slice(&arr[..mid]);
+    let (mut left, mut right, mut out) = (0, mid, 0);
+    while left < buf.len() && right < arr.len() {
+        if cmp(&arr[right], &buf[left]) == Ordering::Less {
+            arr.swap(out, right);
+            right += 1;
+        } else {
+            std::mem::swap(&mut arr[out], &mut buf[left]);
+            left += 1;
+        }
+        out += 1;
+    }
+    for val in buf.drain(left..) {
+        arr[out] = val;
+        out += 1;
+    }
+}
+
+/// A stable merge sort that allocates nothing: halves are merged by
+/// rotating blocks into place. O(n log² n), and T need not be `Clone`.
+pub fn merge_sort_in_place_by<T, F>(arr: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    merge_sort_in_place_slice(arr, &mut cmp);
+}
+
+fn merge_sort_in_place_slice<T, F>(arr: &mut [T], cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if arr.len() < INSERTION_SORT_LEN {
+        insertion_sort_by(arr, cmp);
+        return;
+    }
+    let mid = arr.len() / 2;
+    merge_sort_in_place_slice(&mut arr[..mid], cmp);
+    merge_sort_in_place_slice(&mut arr[mid..], cmp);
+    merge_in_place(arr, mid, cmp);
+}
+
+/// Splits the longer half in the middle, finds where that element belongs
+/// in the other half, and rotates so both halves split at the same point.
+/// That leaves two smaller merges.
+fn merge_in_place<T, F>(arr: &mut [T], mid: usize, cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if mid == 0 || mid == arr.len() || cmp(&arr[mid], &arr[mid - 1]) != Ordering::Less {
+        return;
+    }
+    let (left_cut, right_cut) = if mid >= arr.len() - mid {
+        let left_cut = mid / 2;
+        let pivot = &arr[left_cut];
+        let right_cut = mid + arr[mid..].partition_point(|val| cmp(val, pivot) == Ordering::Less);
+        (left_cut, right_cut)
+    } else {
+        let right_cut = mid + (arr.len() - mid) / 2;
+        let pivot = &arr[right_cut];
+        let left_cut = arr[..mid].partition_point(|val| cmp(pivot, val) != Ordering::Less);
+        (left_cut, right_cut)
+    };
+    arr[left_cut..right_cut].rotate_left(mid - left_cut);
+    let new_mid = left_cut + (right_cut - mid);
+    merge_in_place(&mut arr[..new_mid], left_cut, cmp);
+    merge_in_place(&mut arr[new_mid..], right_cut - new_mid, cmp);
+}
+
+pub fn timsort<T: Ord + Clone>(arr: &mut [T]) {
+    timsort_by(arr, T::cmp);
+}
+
+/// Merges below this many wins in a row compare one pair at a time.
+const MIN_GALLOP: usize = 7;
+
+/// A stable, adaptive merge sort. It splits the input into the runs that
+/// are already there, extends short ones to `minrun` with binary insertion
+/// sort, and merges them off a stack that keeps run lengths growing like
+/// Fibonacci numbers, so merges stay balanced. When one run keeps winning,
+/// merges switch to galloping (exponential search) through it.
+///
+/// Sorted, reversed, or sorted-then-appended input takes close to O(n).
+pub fn timsort_by<T, F>(arr: &mut [T], mut cmp: F)
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let minrun = minrun(arr.len());
+    let mut merger = TimMerger {
+        buf: Vec::new(),
+        min_gallop: MIN_GALLOP,
+    };
+    // (start, len) of each run not merged yet.
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    let mut start = 0;
+    while start < arr.len() {
+        let rest = &mut arr[start..];
+        let mut len = find_run(rest, &mut cmp);
+        if len < minrun {
+            let extended = minrun.min(rest.len());
+            binary_insertion_sort(&mut rest[..extended], len, &mut cmp);
+            len = extended;
+        }
+        runs.push((start, len));
+        start += len;
+
+        // With A, B, C the top three runs, keep A > B + C and B > C.
+        while runs.len() > 1 {
+            let mut n = runs.len() - 2;
+            let len = |idx: usize| runs[idx].1;
+            if (n >= 1 && len(n - 1) <= len(n) + len(n + 1))
+                || (n >= 2 && len(n - 2) <= len(n - 1) + len(n))
+            {
+                if len(n - 1) < len(n + 1) {
+                    n -= 1;
+                }
+            } else if len(n) > len(n + 1) {
+                break;
+            }
+            merger.merge_at(arr, &mut runs, n, &mut cmp);
+        }
+    }
+
+    while runs.len() > 1 {
+        let mut n = runs.len() - 2;
+        if n >= 1 && runs[n - 1].1 < runs[n + 1].1 {
+            n -= 1;
+        }
+        merger.merge_at(arr, &mut runs, n, &mut cmp);
+    }
+}
+
+/// A run length between 32 and 64 such that `len / minrun` is a power of
+/// two or just below one, which keeps the final merges balanced.
+fn minrun(mut len: usize) -> usize {
+    let mut extra = 0;
+    while len >= 64 {
+        extra |= len & 1;
+        len >>= 1;
+    }
+    len + extra
+}
+
+/// Insertion sort that binary searches for each insertion point. `arr[..sorted]`
+/// must already be sorted.
+fn binary_insertion_sort<T, F>(arr: &mut [T], sorted: usize, cmp: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    for i in sorted.max(1)..arr.len() {
+        let val = &arr[i];
+        let pos = arr[..i].partition_point(|other| cmp(val, other) != Ordering::Less);
+        arr[pos..=i].rotate_right(1);
+    }
+}
+
+/// How many elements at the start of `arr` match `pred`, which must hold
+/// for a prefix. Probes at 1, 2, 4, ... before binary searching, so it is
+/// O(log k) for an answer of k.
+fn gallop<T>(arr: &[T], mut pred: impl FnMut(&T) -> bool) -> usize {
+    let mut known = 0;
+    let mut probe = 1;
+    while probe <= arr.len() && pred(&arr[probe - 1]) {
+        known = probe;
+        probe *= 2;
+    }
+    let end = arr.len().min(probe - 1);
+    known + arr[known..end].partition_point(pred)
+}
+
+/// Like `gallop`, but counts a matching suffix, probing from the end.
+fn gallop_back<T>(arr: &[T], mut pred: impl FnMut(&T) -> bool) -> usize {
+    let len = arr.len();
+    let mut known = 0;
+    let mut probe = 1;
+    while probe <= len && pred(&arr[len - probe]) {
+        known = probe;
+        probe *= 2;
+    }
+    let end = len.min(probe - 1);
+    let region = &arr[len - end..len - known];
+    known + region.len() - region.partition_point(|val| !pred(val))
+}
+
+struct TimMerger<T> {
+    buf: Vec<T>,
+    // Lowered while galloping pays off and raised when it doesn't.
+    min_gallop: usize,
+}
+
+impl<T: Clone> TimMerger<T> {
+    /// Merges run `idx` with the one after it.
+    fn merge_at<F>(
+        &mut self,
+        arr: &mut [T],
+        runs: &mut Vec<(usize, usize)>,
+        idx: usize,
+        cmp: &mut F,
+    ) where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let (start, len_a) = runs[idx];
+        let (_, len_b) = runs.remove(idx + 1);
+        runs[idx].1 = len_a + len_b;
+        let arr = &mut arr[start..start + len_a + len_b];
+
+        // Elements of A no greater than B's first, and elements of B less
+        // than A's last, are already where they belong.
+        let (left, right) = arr.split_at(len_a);
+        let skip = gallop(left, |val| cmp(&right[0], val) != Ordering::Less);
+        let keep = gallop(right, |val| cmp(val, &left[len_a - 1]) == Ordering::Less);
+        if skip == len_a || keep == 0 {
+            return;
+        }
+        let arr = &mut arr[skip..len_a + keep];
+        let mid = len_a - skip;
+        if mid <= keep {
+            self.merge_lo(arr, mid, cmp);
+        } else {
+            self.merge_hi(arr, mid, cmp);
+        }
+    }
+
+    /// Merges front to back with the shorter left run in `buf`. Positions
+    /// between `out` and `right` are gaps whose contents don't matter.
+    fn merge_lo<F>(&mut self, arr: &mut [T], mid: usize, cmp: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let buf = &mut self.buf;
+        buf.clear();
+        buf.extend_from_slice(&arr[..mid]);
+        let (mut left, mut right, mut out) = (0, mid, 0);
+        'merge: while left < buf.len() && right < arr.len() {
+            let (mut left_wins, mut right_wins) = (0, 0);
+            while left_wins.max(right_wins) < self.min_gallop {
+                if cmp(&arr[right], &buf[left]) == Ordering::Less {
+                    arr.swap(out, right);
+                    right += 1;
+                    right_wins += 1;
+                    left_wins = 0;
+                } else {
+                    std::mem::swap(&mut arr[out], &mut buf[left]);
+                    left += 1;
+                    left_wins += 1;
+                    right_wins = 0;
+                }
+                out += 1;
+                if left == buf.len() || right == arr.len() {
+                    break 'merge;
+                }
+            }
+
+            loop {
+                let next = &arr[right];
+                let from_left = gallop(&buf[left..], |val| cmp(next, val) != Ordering::Less);
+                for _ in 0..from_left {
+                    std::mem::swap(&mut arr[out], &mut buf[left]);
+                    left += 1;
+                    out += 1;
+                }
+                if left == buf.len() {
+                    break 'merge;
+                }
+                let next = &buf[left];
+                let from_right = gallop(&arr[right..], |val| cmp(val, next) == Ordering::Less);
+                for _ in 0..from_right {
+                    arr.swap(out, right);
+                    right += 1;
+                    out += 1;
+                }
+                if right == arr.len() {
+                    break 'merge;
+                }
+                if from_left < MIN_GALLOP && from_right < MIN_GALLOP {
+                    self.min_gallop += 1;
+                    break;
+                }
+                self.min_gallop = self.min_gallop.saturating_sub(1).max(1);
+            }
+        }
+        for val in buf.drain(left..) {
+            arr[out] = val;
+            out += 1;
+        }
+    }
+
+    /// Merges back to front with the shorter right run in `buf`. Positions
+    /// between `left` and `out` are gaps.
+    fn merge_hi<F>(&mut self, arr: &mut [T], mid: usize, cmp: &mut F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let buf = &mut self.buf;
+        buf.clear();
+        buf.extend_from_slice(&arr[mid..]);
+        // `left` and `right` count what is left of each run.
+        let (mut left, mut right, mut out) = (mid, buf.len(), arr.len());
+        'merge: while left > 0 && right > 0 {
+            let (mut left_wins, mut right_wins) = (0, 0);
+            while left_wins.max(right_wins) < self.min_gallop {
+                out -= 1;
+                if cmp(&buf[right - 1], &arr[left - 1]) == Ordering::Less {
+                    arr.swap(out, left - 1);
+                    left -= 1;
+                    left_wins += 1;
+                    right_wins = 0;
+                } else {
+                    std::mem::swap(&mut arr[out], &mut buf[right - 1]);
+                    right -= 1;
+                    right_wins += 1;
+                    left_wins = 0;
+                }
+                if left == 0 || right == 0 {
+                    break 'merge;
+                }
+            }
+
+            loop {
+                let next = &buf[right - 1];
+                let from_left = gallop_back(&arr[..left], |val| cmp(next, val) == Ordering::Less);
+                for _ in 0..from_left {
+                    out -= 1;
+                    arr.swap(out, left - 1);
+                    left -= 1;
+                }
+                if left == 0 {
+                    break 'merge;
+                }
+                let next = &arr[left - 1];
+                let from_right = gallop_back(&buf[..right], |val| cmp(val, next) != Ordering::Less);
+                for _ in 0..from_right {
+                    out -= 1;
+                    std::mem::swap(&mut arr[out], &mut buf[right - 1]);
+                    right -= 1;
+                }
+                if right == 0 {
+                    break 'merge;
+                }
+                if from_left < MIN_GALLOP && from_right < MIN_GALLOP {
+                    self.min_gallop += 1;
+                    break;
+                }
+                self.min_gallop = self.min_gallop.saturating_sub(1).max(1);
+            }
+        }
+        for (idx, val) in buf.drain(..right).enumerate() {
+            arr[left + idx] = val;
+        }
+    }
+}
+
+pub fn pdqsort<T: Ord>(arr: &mut [T]) {
+    pdqsort_by(arr, T::cmp);
+}
+
+/// Partitions up to this length are insertion sorted.
+const PDQ_INSERTION_SORT_LEN: usize = 20;
+/// Elements classified at a time by the block partition.
+const BLOCK: usize = 128;
+
+/// Pattern-defeating quicksort: an unstable introsort that partitions in
+/// branch-free blocks and adapts to its input.
+///
+/// - A partition that found nothing out of place followed by a pivot that
+///   looks sorted triggers a bounded insertion sort, so sorted and nearly
+///   sorted input take O(n).
+/// - A pivot no greater than the pivot of the partition to the left is
+///   repeated, so everything equal to it is split off in one pass and runs
+///   of duplicates take O(n).
+/// - After a lopsided partition a few elements are shuffled to break up
+///   whatever pattern caused it, and after log₂ n of them it hands over to
+///   heapsort.
+pub fn pdqsort_by<T, F>(arr: &mut [T], mut cmp: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let limit = usize::BITS - arr.len().leading_zeros();
+    pdqsort_slice(arr, &mut cmp, None, limit);
+}
+
+fn pdqsort_slice<'a, T, F>(
+    mut arr: &'a mut [T],
+    cmp: &mut F,
+    mut pred: Option<&'a T>,
+    mut limit: u32,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut was_balanced = true;
+    let mut was_partitioned = true;
+    loop {
+        let len = arr.len();
+        if len <= PDQ_INSERTION_SORT_LEN {
+            insertion_sort_by(arr, cmp);
+            return;
+        }
+        if limit == 0 {
+            heapsort_by(arr, cmp);
+            return;
+        }
+        if !was_balanced {
+            break_patterns(arr);
+            limit -= 1;
+        }
+
+        let (pivot_idx, likely_sorted) = choose_pdq_pivot(arr, cmp);
+        if was_balanced && was_partitioned && likely_sorted && partial_insertion_sort(arr, cmp) {
+            return;
+        }
+
+        // Everything here is at least `pred`, so a pivot equal to it is the
+        // smallest value present: split off all its copies and move on.
+        if let Some(pred) = pred {
+            if cmp(pred, &arr[pivot_idx]) != Ordering::Less {
+                let mid = partition_equal(arr, pivot_idx, cmp);
+                arr = &mut arr[mid..];
+                continue;
+            }
+        }
+
+        let (mid, partitioned) = partition_blocks(arr, pivot_idx, cmp);
+        was_balanced = mid.min(len - mid) >= len / 8;
+        was_partitioned = partitioned;
+
+        let (left, right) = arr.split_at_mut(mid);
+        let (pivot, right) = right.split_at_mut(1);
+        let pivot = &pivot[0];
+        if left.len() < right.len() {
+            pdqsort_slice(left, cmp, pred, limit);
+            arr = right;
+            pred = Some(pivot);
+        } else {
+            pdqsort_slice(right, cmp, Some(pivot), limit);
+            arr = left;
+        }
+    }
+}
+
+/// Picks a pivot like `choose_pivot` while counting the swaps needed to
+/// order the samples. None suggests the slice is sorted; all of them that
+/// it is reversed, in which case it is reversed back first.
+fn choose_pdq_pivot<T, F>(arr: &mut [T], cmp: &mut F) -> (usize, bool)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    const MAX_SWAPS: usize = 4 * 3;
+    let len = arr.len();
+    let (mut a, mut b, mut c) = (len / 4, len / 4 * 2, len / 4 * 3);
+    let mut swaps = 0;
+    {
+        let mut sort2 = |a: &mut usize, b: &mut usize| {
+            if cmp(&arr[*b], &arr[*a]) == Ordering::Less {
+                std::mem::swap(a, b);
+                swaps += 1;
+            }
+        };
+        let mut sort3 = |a: &mut usize, b: &mut usize, c: &mut usize| {
+            sort2(a, b);
+            sort2(b, c);
+            sort2(a, b);
+        };
+        if len >= NINTHER_LEN {
+            for mid in [&mut a, &mut b, &mut c] {
+                let (mut low, mut high) = (*mid - 1, *mid + 1);
+                sort3(&mut low, mid, &mut high);
+            }
+        }
+        sort3(&mut a, &mut b, &mut c);
+    }
+
+    if swaps < MAX_SWAPS {
+        (b, swaps == 0)
+    } else {
+        arr.reverse();
+        (len - 1 - b, true)
+    }
+}
+
+/// Swaps a few elements in the middle to pseudo-random places. Seeded by
+/// the length, so sorting is still deterministic.
+fn break_patterns<T>(arr: &mut [T]) {
+    let len = arr.len();
+    let mut seed = len as u64;
+    let mut next = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed as usize
+    };
+    let mask = len.next_power_of_two() - 1;
+    let pos = len / 4 * 2;
+    for i in 0..3 {
+        let mut other = next() & mask;
+        if other >= len {
+            other -= len;
+        }
+        arr.swap(pos - 1 + i, other);
+    }
+}
+
+/// Fixes a few out-of-order elements with insertion sort and reports
+/// whether that sorted the whole slice. Gives up after five of them.
+fn partial_insertion_sort<T, F>(arr: &mut [T], cmp: &mut F) -> bool
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    const MAX_STEPS: usize = 5;
+    // Shifting costs too much on long slices that won't end up sorted.
+    const SHORTEST_SHIFTING: usize = 50;
+    let len = arr.len();
+    let mut i = 1;
+    for _ in 0..MAX_STEPS {
+        while i < len && cmp(&arr[i], &arr[i - 1]) != Ordering::Less {
+            i += 1;
+        }
+        if i == len {
+            return true;
+        }
+        if len < SHORTEST_SHIFTING {
+            return false;
+        }
+        arr.swap(i - 1, i);
+        let mut j = i - 1;
+        while j > 0 && cmp(&arr[j], &arr[j - 1]) == Ordering::Less {
+            arr.swap(j - 1, j);
+            j -= 1;
+        }
+        let mut j = i;
+        while j + 1 < len && cmp(&arr[j + 1], &arr[j]) == Ordering::Less {
+            arr.swap(j, j + 1);
+            j += 1;
+        }
+    }
+    false
+}
+
+/// Moves everything no greater than the pivot in front of everything
+/// greater, assuming nothing is less than it, and returns the length of
+/// the front part.
+fn partition_equal<T, F>(arr: &mut [T], pivot_idx: usize, cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    arr.swap(0, pivot_idx);
+    let (pivot, rest) = arr.split_at_mut(1);
+    let pivot = &pivot[0];
+    let (mut low, mut high) = (0, rest.len());
+    loop {
+        while low < high && cmp(pivot, &rest[low]) != Ordering::Less {
+            low += 1;
+        }
+        while low < high && cmp(pivot, &rest[high - 1]) == Ordering::Less {
+            high -= 1;
+        }
+        if low >= high {
+            return low + 1;
+        }
+        high -= 1;
+        rest.swap(low, high);
+        low += 1;
+    }
+}
+
+/// Partitions around the pivot and returns its final index, along with
+/// whether the slice was already partitioned.
+fn partition_blocks<T, F>(arr: &mut [T], pivot_idx: usize, cmp: &mut F) -> (usize, bool)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    arr.swap(0, pivot_idx);
+    let (pivot_slot, rest) = arr.split_at_mut(1);
+    let pivot = &pivot_slot[0];
+
+    // Skip the elements already on the correct side.
+    let (mut low, mut high) = (0, rest.len());
+    while low < high && cmp(&rest[low], pivot) == Ordering::Less {
+        low += 1;
+    }
+    while low < high && cmp(&rest[high - 1], pivot) != Ordering::Less {
+        high -= 1;
+    }
+    let mid = low + partition_in_blocks(&mut rest[low..high], pivot, cmp);
+    arr.swap(0, mid);
+    (mid, low >= high)
+}
+
+/// BlockQuicksort partitioning. A block from each end is scanned and the
+/// offsets of misplaced elements are recorded without branching on the
+/// comparison; then the recorded elements are swapped in pairs. Returns
+/// how many elements are less than the pivot.
+fn partition_in_blocks<T, F>(arr: &mut [T], pivot: &T, cmp: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let (mut low, mut high) = (0, arr.len());
+    let mut offsets_low = [0u8; BLOCK];
+    let mut offsets_high = [0u8; BLOCK];
+    let (mut start_low, mut end_low) = (0, 0);
+    let (mut start_high, mut end_high) = (0, 0);
+    let (mut block_low, mut block_high) = (BLOCK, BLOCK);
+
+    loop {
+        let is_done = high - low <= 2 * BLOCK;
+        if is_done {
+            // Size the last blocks to cover exactly what is left, minus
+            // a block whose offsets are still pending.
+            let mut rem = high - low;
+            if start_low < end_low || start_high < end_high {
+                rem -= BLOCK;
+            }
+            if start_low < end_low {
+                block_high = rem;
+            } else if start_high < end_high {
+                block_low = rem;
+            } else {
+                block_low = rem / 2;
+                block_high = rem - block_low;
+            }
+        }
+
+        if start_low == end_low {
+            start_low = 0;
+            end_low = 0;
+            for i in 0..block_low {
+                offsets_low[end_low] = i as u8;
+                end_low += (cmp(&arr[low + i], pivot) != Ordering::Less) as usize;
+            }
+        }
+        if start_high == end_high {
+            start_high = 0;
+            end_high = 0;
+            for i in 0..block_high {
+                offsets_high[end_high] = i as u8;
+                end_high += (cmp(&arr[high - 1 - i], pivot) == Ordering::Less) as usize;
+            }
+        }
+
+        let count = (end_low - start_low).min(end_high - start_high);
+        for k in 0..count {
+            let left = low + offsets_low[start_low + k] as usize;
+            let right = high - 1 - offsets_high[start_high + k] as usize;
+            arr.swap(left, right);
+        }
+        start_low += count;
+        start_high += count;
+
+        if start_low == end_low {
+            low += block_low;
+        }
+        if start_high == end_high {
+            high -= block_high;
+        }
+        if is_done {
+            break;
+        }
+    }
+
+    // At most one block still has misplaced elements; move them across the
+    // boundary, which is also where the pivot will go.
+    if start_low < end_low {
+        while start_low < end_low {
+            end_low -= 1;
+            high -= 1;
+            arr.swap(low + offsets_low[end_low] as usize, high);
+        }
+        high
+    } else {
+        while start_high < end_high {
+            end_high -= 1;
+            arr.swap(low, high - 1 - offsets_high[end_high] as usize);
+            low += 1;
+        }
+        low
+    }
+}
+
+/// Types `radix_sort` can sort, mapped to unsigned integers that order
+/// the same way.
+pub trait RadixSortKey: Copy {
+    /// How many low bytes of `radix_key` are used.
+    const BYTES: usize;
+
+    fn radix_key(self) -> u64;
+}
+
+macro_rules! radix_sort_unsigned {
+    ($($t:ty),*) => {$(
+        impl RadixSortKey for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+
+            fn radix_key(self) -> u64 {
+                self as u64
+            }
+        }
+    )*};
+}
+
+// Flipping the sign bit moves negative values below positive ones.
+macro_rules! radix_sort_signed {
+    ($($t:ty => $u:ty),*) => {$(
+        impl RadixSortKey for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+
+            fn radix_key(self) -> u64 {
+                (self as $u ^ 1 << (<$u>::BITS - 1)) as u64
+            }
+        }
+    )*};
+}
+
+// Positive floats already order like their bits once the sign bit is set.
+// Negative ones order in reverse, so all their bits are flipped. The result
+// matches `total_cmp`: -NaN < -inf < ... < -0.0 < 0.0 < ... < inf < NaN.
+macro_rules! radix_sort_float {
+    ($($t:ty => $u:ty),*) => {$(
+        impl RadixSortKey for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+
+            fn radix_key(self) -> u64 {
+                let bits = self.to_bits();
+                let sign = 1 << (<$u>::BITS - 1);
+                (if bits & sign == 0 { bits | sign } else { !bits }) as u64
+            }
+        }
+    )*};
+}
+
+radix_sort_unsigned!(u8, u16, u32, u64, usize);
+radix_sort_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize);
+radix_sort_float!(f32 => u32, f64 => u64);
+
+/// LSD radix sort: one stable counting pass per byte, least significant
+/// first, so O(n) for fixed-width keys. Passes where every element has the
+/// same byte are skipped. Floats end up in `total_cmp` order.
+pub fn radix_sort<T: RadixSortKey>(arr: &mut [T]) {
+    if arr.len() < 2 {
+        return;
+    }
+    let mut buf = arr.to_vec();
+    // Whether the latest pass wrote into `buf` rather than `arr`.
+    let mut in_buf = false;
+    for byte in 0..T::BYTES {
+        let shift = byte * 8;
+        let digit = |val: &T| (val.radix_key() >> shift) as usize & 0xff;
+        let (src, dst) = if in_buf {
+            (&mut buf[..], &mut arr[..])
+        } else {
+            (&mut arr[..], &mut buf[..])
+        };
+
+        let mut counts = [0usize; 256];
+        for val in src.iter() {
+            counts[digit(val)] += 1;
+        }
+        if counts.contains(&src.len()) {
+            continue;
+        }
+        let mut next = [0usize; 256];
+        for digit in 1..256 {
+            next[digit] = next[digit - 1] + counts[digit - 1];
+        }
+        for val in src.iter() {
+            let digit = digit(val);
+            dst[next[digit]] = *val;
+            next[digit] += 1;
+        }
+        in_buf = !in_buf;
+    }
+    if in_buf {
+        arr.copy_from_slice(&buf);
+    }
+}
+
+/// Buckets at or below this size are insertion sorted by `msd_radix_sort`.
+const MSD_SMALL_BUCKET: usize = 32;
+
+/// MSD radix sort for byte strings, in place: elements are split into 257
+/// buckets by their byte at the current depth (the first for strings that
+/// have ended), and each bucket is split again on the next byte. Buckets are
+/// permuted into place by swapping, American flag style, so nothing is
+/// cloned. The order is the same as comparing the bytes lexicographically.
+pub fn msd_radix_sort<S: AsRef<[u8]>>(arr: &mut [S]) {
+    fn bucket<S: AsRef<[u8]>>(val: &S, depth: usize) -> usize {
+        val.as_ref().get(depth).map_or(0, |byte| *byte as usize + 1)
+    }
 
-    return switch;
+    // Worked off a stack, since common prefixes can be arbitrarily long.
+    let mut pending = vec![(0, arr.len(), 0)];
+    while let Some((start, end, depth)) = pending.pop() {
+        let arr = &mut arr[start..end];
+        if arr.len() <= MSD_SMALL_BUCKET {
+            insertion_sort_by(arr, &mut |a: &S, b: &S| {
+                a.as_ref()[depth..].cmp(&b.as_ref()[depth..])
+            });
+            continue;
+        }
+
+        let mut counts = [0usize; 257];
+        for val in arr.iter() {
+            counts[bucket(val, depth)] += 1;
+        }
+        let mut ends = [0usize; 257];
+        let mut total = 0;
+        for (bucket, count) in counts.iter().enumerate() {
+            total += count;
+            ends[bucket] = total;
+        }
+        let mut next: Vec<usize> = (0..257)
+            .map(|bucket| ends[bucket] - counts[bucket])
+            .collect();
+
+        // Follow each misplaced element to its bucket, swapping whatever is
+        // there back, until the element that belongs here turns up.
+        for idx in 0..257 {
+            while next[idx] < ends[idx] {
+                let mut target = bucket(&arr[next[idx]], depth);
+                while target != idx {
+                    arr.swap(next[idx], next[target]);
+                    next[target] += 1;
+                    target = bucket(&arr[next[idx]], depth);
+                }
+                next[idx] += 1;
+            }
+        }
+
+        // Strings in bucket 0 have ended and are all equal.
+        for idx in 1..257 {
+            if counts[idx] > 1 {
+                pending.push((
+                    start + ends[idx] - counts[idx],
+                    start + ends[idx],
+                    depth + 1,
+                ));
+            }
+        }
+    }
+}
+
+/// The most distinct keys `counting_sort` will allocate counts for.
+pub const MAX_COUNTING_RANGE: usize = 1 << 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortError {
+    /// `counting_sort` keys span more than `MAX_COUNTING_RANGE` values.
+    RangeTooLarge { range: u128 },
+    /// `bucket_sort` got a NaN or an infinity, which no bucket can hold.
+    NotFinite,
+}
+
+impl fmt::Display for SortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortError::RangeTooLarge { range } => write!(
+                f,
+                "keys span {} values, more than the {} counting sort allows",
+                range, MAX_COUNTING_RANGE
+            ),
+            SortError::NotFinite => write!(f, "cannot bucket NaN or infinite values"),
+        }
+    }
+}
+
+impl std::error::Error for SortError {}
+
+/// A stable counting sort by the integer key `key` extracts, O(n + k) for
+/// keys spanning k values. Fails without touching `arr` if k is over
+/// `MAX_COUNTING_RANGE`.
+pub fn counting_sort<T, F>(arr: &mut [T], mut key: F) -> Result<(), SortError>
+where
+    F: FnMut(&T) -> i64,
+{
+    let keys: Vec<i64> = arr.iter().map(&mut key).collect();
+    let (Some(min), Some(max)) = (keys.iter().min(), keys.iter().max()) else {
+        return Ok(());
+    };
+    let range = (*max as i128 - *min as i128 + 1) as u128;
+    if range > MAX_COUNTING_RANGE as u128 {
+        return Err(SortError::RangeTooLarge { range });
+    }
+
+    let offset = |key: i64| (key as i128 - *min as i128) as usize;
+    let mut next = vec![0usize; range as usize];
+    for key in keys.iter() {
+        next[offset(*key)] += 1;
+    }
+    let mut total = 0;
+    for count in next.iter_mut() {
+        (*count, total) = (total, total + *count);
+    }
+    // Where each element goes, then swap along the cycles of that
+    // permutation so `T` needs no `Clone`.
+    let mut dest: Vec<usize> = keys
+        .iter()
+        .map(|key| {
+            let slot = &mut next[offset(*key)];
+            *slot += 1;
+            *slot - 1
+        })
+        .collect();
+    for idx in 0..arr.len() {
+        while dest[idx] != idx {
+            let target = dest[idx];
+            arr.swap(idx, target);
+            dest.swap(idx, target);
+        }
+    }
+    Ok(())
+}
+
+/// Sorts floats by spreading them over one bucket per element between the
+/// minimum and maximum and insertion sorting each bucket. O(n) expected
+/// for uniformly distributed input, O(n²) if everything lands in one bucket.
+/// Fails without touching `arr` if it holds a NaN or an infinity. Orders
+/// like `f64::total_cmp`, so -0.0 comes before 0.0.
+pub fn bucket_sort(arr: &mut [f64]) -> Result<(), SortError> {
+    if arr.iter().any(|val| !val.is_finite()) {
+        return Err(SortError::NotFinite);
+    }
+    let len = arr.len();
+    if len < 2 {
+        return Ok(());
+    }
+    let min = *arr.iter().min_by(|a, b| a.total_cmp(b)).unwrap();
+    let max = *arr.iter().max_by(|a, b| a.total_cmp(b)).unwrap();
+    if min.total_cmp(&max).is_eq() {
+        return Ok(());
+    }
+
+    // Halve only when `max - min` overflows to infinity. Halving always
+    // would flush the smallest subnormals to zero.
+    let scale = if (max - min).is_finite() { 1. } else { 0.5 };
+    let width = max * scale - min * scale;
+    if !width.is_normal() {
+        // The values are too close together to spread over buckets, such
+        // as -0.0 next to 0.0 or a few subnormals.
+        pdqsort_by(arr, f64::total_cmp);
+        return Ok(());
+    }
+    let mut buckets: Vec<Vec<f64>> = vec![Vec::new(); len];
+    for val in arr.iter() {
+        let idx = ((val * scale - min * scale) / width * len as f64) as usize;
+        buckets[idx.min(len - 1)].push(*val);
+    }
+    let mut out = 0;
+    for mut bucket in buckets {
+        insertion_sort_by(&mut bucket, &mut f64::total_cmp);
+        arr[out..out + bucket.len()].copy_from_slice(&bucket);
+        out += bucket.len();
+    }
+    Ok(())
+}
+
+/// How `par_sort` and `par_quicksort` split up work.
+#[derive(Debug, Clone, Copy)]
+pub struct ParConfig {
+    /// The most threads to sort on, counting the caller's. 0 counts as 1.
+    pub threads: usize,
+    /// Slices up to this long are sorted on a single thread.
+    pub cutoff: usize,
+}
+
+impl Default for ParConfig {
+    /// One thread per core and a cutoff of 16K elements.
+    fn default() -> Self {
+        Self {
+            threads: std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+            cutoff: 1 << 14,
+        }
+    }
+}
+
+pub fn par_sort<T: Ord + Clone + Send + Sync>(arr: &mut [T], config: &ParConfig) {
+    par_sort_by(arr, config, T::cmp);
+}
+
+/// A stable parallel merge sort. Halves are sorted on separate threads,
+/// down to `timsort_by` below the cutoff, and then merged in parallel too:
+/// the longer run is split at its middle, the shorter one where that
+/// element belongs, and the two pairs are merged into the two halves of
+/// the output at once.
+pub fn par_sort_by<T, F>(arr: &mut [T], config: &ParConfig, cmp: F)
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let threads = config.threads.max(1);
+    if threads == 1 || arr.len() <= config.cutoff {
+        timsort_by(arr, &cmp);
+        return;
+    }
+    let mut buf = arr.to_vec();
+    par_merge_sort(arr, &mut buf, false, threads, config.cutoff, &cmp);
+}
+
+/// Sorts into `arr`, or into `buf` if `into_buf` is set. Both must hold the
+/// same elements going in, so each level can sort its halves into one and
+/// merge them into the other without copying anything back.
+fn par_merge_sort<T, F>(
+    arr: &mut [T],
+    buf: &mut [T],
+    into_buf: bool,
+    threads: usize,
+    cutoff: usize,
+    cmp: &F,
+) where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if threads == 1 || arr.len() <= cutoff {
+        timsort_by(if into_buf { buf } else { arr }, cmp);
+        return;
+    }
+    let mid = arr.len() / 2;
+    {
+        let (left, right) = arr.split_at_mut(mid);
+        let (left_buf, right_buf) = buf.split_at_mut(mid);
+        std::thread::scope(|scope| {
+            scope.spawn(|| par_merge_sort(left, left_buf, !into_buf, threads / 2, cutoff, cmp));
+            par_merge_sort(
+                right,
+                right_buf,
+                !into_buf,
+                threads - threads / 2,
+                cutoff,
+                cmp,
+            );
+        });
+    }
+    let (src, dst) = if into_buf { (&*arr, buf) } else { (&*buf, arr) };
+    let (left, right) = src.split_at(mid);
+    par_merge(left, right, dst, threads, cutoff, cmp);
+}
+
+/// Merges `left` and `right` into `out`, taking from `left` on ties.
+fn par_merge<T, F>(left: &[T], right: &[T], out: &mut [T], threads: usize, cutoff: usize, cmp: &F)
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if threads == 1 || out.len() <= cutoff || left.is_empty() || right.is_empty() {
+        let (mut i, mut j) = (0, 0);
+        for slot in out.iter_mut() {
+            if j == right.len() || (i < left.len() && cmp(&right[j], &left[i]) != Ordering::Less) {
+                slot.clone_from(&left[i]);
+                i += 1;
+            } else {
+                slot.clone_from(&right[j]);
+                j += 1;
+            }
+        }
+        return;
+    }
+    // Equal elements must stay on the side that keeps `left` first.
+    let (left_mid, right_mid) = if left.len() >= right.len() {
+        let left_mid = left.len() / 2;
+        let pivot = &left[left_mid];
+        (
+            left_mid,
+            right.partition_point(|val| cmp(val, pivot) == Ordering::Less),
+        )
+    } else {
+        let right_mid = right.len() / 2;
+        let pivot = &right[right_mid];
+        (
+            left.partition_point(|val| cmp(pivot, val) != Ordering::Less),
+            right_mid,
+        )
+    };
+    let (left_low, left_high) = left.split_at(left_mid);
+    let (right_low, right_high) = right.split_at(right_mid);
+    let (out_low, out_high) = out.split_at_mut(left_mid + right_mid);
+    std::thread::scope(|scope| {
+        scope.spawn(|| par_merge(left_low, right_low, out_low, threads / 2, cutoff, cmp));
+        par_merge(
+            left_high,
+            right_high,
+            out_high,
+            threads - threads / 2,
+            cutoff,
+            cmp,
+        );
+    });
+}
+
+pub fn par_quicksort<T: Ord + Send>(arr: &mut [T], config: &ParConfig) {
+    par_quicksort_by(arr, config, T::cmp);
+}
+
+/// An unstable parallel quicksort that needs no extra memory. Each
+/// partition runs on one thread, then the two sides are sorted on separate
+/// threads, shared out by size; below the cutoff it is `pdqsort_by`.
+///
+/// Only the partitions are sequential, so with p threads the first one,
+/// over the whole slice, bounds the speedup more than anything else.
+pub fn par_quicksort_by<T, F>(arr: &mut [T], config: &ParConfig, cmp: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    par_quicksort_slice(arr, config.threads.max(1), config.cutoff, &cmp);
+}
+
+fn par_quicksort_slice<T, F>(arr: &mut [T], threads: usize, cutoff: usize, cmp: &F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let mut cmp_mut = |a: &T, b: &T| cmp(a, b);
+    if threads == 1 || arr.len() <= cutoff.max(PDQ_INSERTION_SORT_LEN) {
+        pdqsort_by(arr, cmp_mut);
+        return;
+    }
+    let pivot_idx = choose_pivot(arr, &mut cmp_mut);
+    let (mid, _) = partition_blocks(arr, pivot_idx, &mut cmp_mut);
+
+    // A lopsided split hands most threads to the long side, and every
+    // level takes at least one thread, so bad pivots can't recurse for long.
+    let len = arr.len();
+    let left_threads = (threads * mid / len).clamp(1, threads - 1);
+    let (left, right) = arr.split_at_mut(mid);
+    let right = &mut right[1..];
+    std::thread::scope(|scope| {
+        scope.spawn(|| par_quicksort_slice(left, left_threads, cutoff, cmp));
+        par_quicksort_slice(right, threads - left_threads, cutoff, cmp);
+    });
 }
 
 pub struct RingBuffer<T> {
@@ -144,40 +1558,33 @@ mod tests {
     fn test_linear_search() {
         // test found
         let arr = [1, 2, 3, 4, 5];
-        let target = 3;
-        let low = 0;
-        let high = arr.len();
-        assert_eq!(binary_search(&arr, target, low, high), Some(2));
-
-        let target = 5;
-        assert_eq!(binary_search(&arr, target, low, high), Some(4));
-
-        let target = 1;
-        assert_eq!(binary_search(&arr, target, low, high), Some(0));
+        assert_eq!(binary_search(&arr, &3), Some(2));
+        assert_eq!(binary_search(&arr, &5), Some(4));
+        assert_eq!(binary_search(&arr, &1), Some(0));
 
         // test not found
-        let arr = [1, 2, 3, 4, 5];
-        let target = 6;
-        let low = 0;
-        let high = arr.len() - 1;
-        assert_eq!(binary_search(&arr, target, low, high), None);
-
-        let target = 0;
-        assert_eq!(binary_search(&arr, target, low, high), None);
+        assert_eq!(binary_search(&arr, &6), None);
+        assert_eq!(binary_search(&arr, &0), None);
 
         // test slice
         let arr = [1, 2, 3, 4, 5, 6, 7, 9, 10];
-        let target = 5;
-        let low = 2;
-        let high = 6;
-        assert_eq!(binary_search(&arr, target, low, high), Some(4));
+        assert_eq!(binary_search(&arr[2..6], &5), Some(2));
+        assert_eq!(binary_search(&arr[2..6], &7), None);
 
         // test empty arr
-        let arr = [];
-        let target = 5;
-        let low = 0;
-        let high = arr.len();
-        assert_eq!(binary_search(&arr, target, low, high), None);
+        let arr: [i64; 0] = [];
+        assert_eq!(binary_search(&arr, &5), None);
+    }
+
+    #[test]
+    fn test_binary_search_by() {
+        let people = [("bob", 25), ("ann", 31), ("eve", 40)];
+        assert_eq!(binary_search_by_key(&people, &31, |(_, age)| *age), Some(1));
+        assert_eq!(binary_search_by_key(&people, &30, |(_, age)| *age), None);
+
+        let desc = [9, 7, 4, 1];
+        assert_eq!(binary_search_by(&desc, |val| 4.cmp(val)), Some(2));
+        assert_eq!(binary_search_by(&desc, |val| 5.cmp(val)), None);
     }
 
     #[test]
@@ -224,7 +1631,7 @@ mod tests {
         assert_eq!(arr, [1, 5, 6, 53, 100, 278]);
 
         // test empty
-        let mut arr = [];
+        let mut arr: [i64; 0] = [];
         bubble_sort(&mut arr);
         assert_eq!(arr, []);
     }
@@ -270,15 +1677,473 @@ mod tests {
     #[test]
     fn test_quicksort() {
         let mut arr = [5, 4, 3, 2, 1];
-        quicksort(&mut arr, 0, 4);
+        quicksort(&mut arr);
         assert_eq!(arr, [1, 2, 3, 4, 5]);
 
         arr = [1, 2, 3, 4, 5];
-        quicksort(&mut arr, 0, 4);
+        quicksort(&mut arr);
         assert_eq!(arr, [1, 2, 3, 4, 5]);
 
         arr = [100, 5, 1, 6, 278];
-        quicksort(&mut arr, 0, 4);
+        quicksort(&mut arr);
         assert_eq!(arr, [1, 5, 6, 100, 278]);
+
+        let mut arr: [i64; 0] = [];
+        quicksort(&mut arr);
+    }
+
+    #[test]
+    fn test_quicksort_by() {
+        let mut words = ["pear", "fig", "apple", "kiwi"].map(String::from);
+        quicksort_by(&mut words, |a, b| b.cmp(a));
+        assert_eq!(words, ["pear", "kiwi", "fig", "apple"]);
+
+        quicksort_by_key(&mut words, |word| word.len());
+        assert_eq!(words[0], "fig");
+        assert_eq!(words[3], "apple");
+    }
+
+    #[test]
+    fn test_quicksort_adversarial_inputs() {
+        let n: i64 = 100_000;
+        let inputs: Vec<Vec<i64>> = vec![
+            (0..n).collect(),
+            (0..n).rev().collect(),
+            vec![7; n as usize],
+            (0..n).map(|i| i.min(n - i)).collect(),
+            (0..n).map(|i| (i * 7919) % 1009).collect(),
+            (0..n).map(|i| if i % 2 == 0 { i } else { n - i }).collect(),
+        ];
+        for input in inputs {
+            let mut expected = input.clone();
+            expected.sort();
+            let mut arr = input;
+            quicksort(&mut arr);
+            assert_eq!(arr, expected);
+        }
+    }
+
+    #[test]
+    fn test_quicksort_falls_back_to_heapsort() {
+        // Every element equal to the pivot goes left, so each partition
+        // only peels off one element until the depth limit kicks in.
+        let n = 100_000u64;
+        let mut arr = vec![0; n as usize];
+        let mut comparisons = 0u64;
+        quicksort_by(&mut arr, |a: &i32, b| {
+            comparisons += 1;
+            a.cmp(b)
+        });
+        assert!(comparisons < 4 * n * n.ilog2() as u64);
+    }
+
+    #[test]
+    fn test_partition() {
+        let mut arr = [7, 2, 9, 1, 5];
+        let pivot_idx = partition(&mut arr);
+        assert_eq!(pivot_idx, 2);
+        assert_eq!(arr[pivot_idx], 5);
+        assert!(arr[..pivot_idx].iter().all(|val| *val <= 5));
+        assert!(arr[pivot_idx + 1..].iter().all(|val| *val > 5));
+
+        let mut arr: [i64; 0] = [];
+        assert_eq!(partition(&mut arr), 0);
+    }
+
+    #[test]
+    fn test_heapsort() {
+        let mut arr = [5, 4, 3, 2, 1];
+        heapsort(&mut arr);
+        assert_eq!(arr, [1, 2, 3, 4, 5]);
+
+        let mut arr = [100, 5, 1, 6, 278, 53, 5];
+        heapsort(&mut arr);
+        assert_eq!(arr, [1, 5, 5, 6, 53, 100, 278]);
+
+        let mut words = ["pear", "apple", "fig"].map(String::from);
+        heapsort(&mut words);
+        assert_eq!(words, ["apple", "fig", "pear"]);
+
+        let mut arr: [i64; 0] = [];
+        heapsort(&mut arr);
+        let mut arr = [1];
+        heapsort(&mut arr);
+        assert_eq!(arr, [1]);
+    }
+
+    /// Sorts `(key, position)` pairs by key and checks equal keys kept
+    /// their order.
+    fn check_stable_sort(
+        sort: fn(&mut [(u32, usize)], fn(&(u32, usize), &(u32, usize)) -> Ordering),
+    ) {
+        let inputs: Vec<Vec<u32>> = vec![
+            (0..1000).map(|i| (i * 7919) % 61).collect(),
+            (0..1000).collect(),
+            (0..1000).rev().map(|i| i / 3).collect(),
+            (0..1000)
+                .map(|i| if i % 100 < 50 { i % 100 } else { 100 - i % 100 })
+                .collect(),
+            vec![4; 100],
+            vec![1],
+            Vec::new(),
+        ];
+        for keys in inputs {
+            let mut arr: Vec<(u32, usize)> = keys.into_iter().zip(0..).collect();
+            let mut expected = arr.clone();
+            expected.sort_by_key(|(key, _)| *key);
+            sort(&mut arr, |a, b| a.0.cmp(&b.0));
+            assert_eq!(arr, expected);
+        }
+    }
+
+    #[test]
+    fn test_merge_sorts_are_stable() {
+        check_stable_sort(|arr, cmp| merge_sort_by(arr, cmp));
+        check_stable_sort(|arr, cmp| merge_sort_bottom_up_by(arr, cmp));
+        check_stable_sort(|arr, cmp| merge_sort_natural_by(arr, cmp));
+        check_stable_sort(|arr, cmp| merge_sort_in_place_by(arr, cmp));
+    }
+
+    #[test]
+    fn test_merge_sort_on_several_keys() {
+        let mut people = [
+            ("ann", 31),
+            ("bob", 25),
+            ("cat", 31),
+            ("dan", 25),
+            ("eve", 40),
+        ];
+        merge_sort(&mut people);
+        merge_sort_by(&mut people, |a, b| a.1.cmp(&b.1));
+        assert_eq!(
+            people,
+            [
+                ("bob", 25),
+                ("dan", 25),
+                ("ann", 31),
+                ("cat", 31),
+                ("eve", 40)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_timsort_is_stable() {
+        check_stable_sort(|arr, cmp| timsort_by(arr, cmp));
+
+        let mut words = ["pear", "fig", "apple", "kiwi", "date"].map(String::from);
+        timsort(&mut words);
+        assert_eq!(words, ["apple", "date", "fig", "kiwi", "pear"]);
+    }
+
+    #[test]
+    fn test_minrun() {
+        assert_eq!(minrun(0), 0);
+        assert_eq!(minrun(63), 63);
+        assert_eq!(minrun(64), 32);
+        assert_eq!(minrun(65), 33);
+        assert_eq!(minrun(1 << 20), 32);
+        assert_eq!(minrun((1 << 20) + 1), 33);
+    }
+
+    #[test]
+    fn test_timsort_adapts_to_presorted_input() {
+        let n = 100_000;
+        let sorted: Vec<u32> = (0..n).collect();
+        let mut appended = sorted.clone();
+        appended.extend((0..100).map(|i| (i * 7919) % n));
+        let mut rotated: Vec<u32> = (0..n / 2).collect();
+        rotated.extend(n / 2..n);
+        rotated[..n as usize / 2].rotate_left(1000);
+        let shuffled: Vec<u32> = (0..n).map(|i| (i * 7919) % n).collect();
+        let cases = [
+            (sorted.clone(), n),
+            (sorted.iter().rev().copied().collect(), n),
+            (appended, 2 * n),
+            (rotated, 2 * n),
+            (shuffled, 20 * n),
+        ];
+        for (input, max_comparisons) in cases {
+            let mut expected = input.clone();
+            expected.sort();
+            let mut arr = input;
+            let mut comparisons = 0;
+            timsort_by(&mut arr, |a, b| {
+                comparisons += 1;
+                a.cmp(b)
+            });
+            assert_eq!(arr, expected);
+            assert!(comparisons <= max_comparisons);
+        }
+    }
+
+    #[test]
+    fn test_pdqsort() {
+        let n: u64 = 100_000;
+        let inputs: Vec<Vec<u64>> = vec![
+            (0..n).map(|i| (i * 7919) % n).collect(),
+            (0..n).map(|i| (i * 7919) % 7).collect(),
+            (0..n).map(|i| i.min(n - i)).collect(),
+            (0..n).map(|i| i % 1000).collect(),
+            (0..n).map(|i| if i % 2 == 0 { i } else { n - i }).collect(),
+            (0..300).map(|i| (i * 31) % 300).collect(),
+            vec![1],
+            Vec::new(),
+        ];
+        for input in inputs {
+            let mut expected = input.clone();
+            expected.sort();
+            let mut arr = input;
+            pdqsort(&mut arr);
+            assert_eq!(arr, expected);
+        }
+
+        let mut words = ["pear", "fig", "apple", "kiwi"].map(String::from);
+        pdqsort_by(&mut words, |a, b| b.cmp(a));
+        assert_eq!(words, ["pear", "kiwi", "fig", "apple"]);
+    }
+
+    #[test]
+    fn test_pdqsort_adapts_to_patterns() {
+        let n = 100_000u64;
+        let sorted: Vec<u64> = (0..n).collect();
+        let mut nearly_sorted = sorted.clone();
+        nearly_sorted.swap(10, 20);
+        nearly_sorted.swap(50_000, 50_001);
+        let cases = [
+            sorted.clone(),
+            sorted.iter().rev().copied().collect(),
+            nearly_sorted,
+            vec![3; n as usize],
+            (0..n).map(|i| i / 10_000).collect(),
+        ];
+        for input in cases {
+            let mut expected = input.clone();
+            expected.sort();
+            let mut arr = input;
+            let mut comparisons = 0u64;
+            pdqsort_by(&mut arr, |a, b| {
+                comparisons += 1;
+                a.cmp(b)
+            });
+            assert_eq!(arr, expected);
+            assert!(comparisons < 4 * n);
+        }
+    }
+
+    #[test]
+    fn test_partition_in_blocks() {
+        for len in [0, 1, 5, 127, 128, 129, 256, 257, 1000, 4099] {
+            let mut arr: Vec<u64> = (0..len).map(|i| (i * 7919) % 1009).collect();
+            let less = arr.iter().filter(|val| **val < 500).count();
+            let mid = partition_in_blocks(&mut arr, &500, &mut u64::cmp);
+            assert_eq!(mid, less);
+            assert!(arr[..mid].iter().all(|val| *val < 500));
+            assert!(arr[mid..].iter().all(|val| *val >= 500));
+        }
+    }
+
+    #[test]
+    fn test_radix_sort_integers() {
+        fn check<T: RadixSortKey + Ord + std::fmt::Debug>(mut arr: Vec<T>) {
+            let mut expected = arr.clone();
+            expected.sort();
+            radix_sort(&mut arr);
+            assert_eq!(arr, expected);
+        }
+
+        let values: Vec<u64> = (0..5000u64)
+            .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15))
+            .collect();
+        check(values.iter().map(|val| *val as u8).collect());
+        check(values.iter().map(|val| *val as u16).collect());
+        check(values.iter().map(|val| *val as u32).collect());
+        check(values.clone());
+        check(values.iter().map(|val| *val as i8).collect());
+        check(values.iter().map(|val| *val as i16).collect());
+        check(values.iter().map(|val| *val as i32).collect());
+        check(values.iter().map(|val| *val as i64).collect());
+        check(vec![i64::MAX, 0, i64::MIN, -1, 1, i64::MIN + 1]);
+        check(vec![u32::MAX, 0, 1, u32::MAX - 1]);
+        check(vec![7i16; 100]);
+        check(Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_radix_sort_floats() {
+        let mut arr = vec![
+            1.5,
+            -0.0,
+            f64::NAN,
+            0.0,
+            f64::NEG_INFINITY,
+            -2.25,
+            f64::INFINITY,
+            -f64::NAN,
+            f64::MIN_POSITIVE,
+            -1e300,
+        ];
+        let mut expected = arr.clone();
+        expected.sort_by(f64::total_cmp);
+        radix_sort(&mut arr);
+        let bits = |arr: &[f64]| arr.iter().map(|val| val.to_bits()).collect::<Vec<_>>();
+        assert_eq!(bits(&arr), bits(&expected));
+
+        let mut arr: Vec<f32> = (0..1000)
+            .map(|i| ((i * 7919) % 1000) as f32 / 7. - 70.)
+            .collect();
+        let mut expected = arr.clone();
+        expected.sort_by(f32::total_cmp);
+        radix_sort(&mut arr);
+        assert_eq!(arr, expected);
+    }
+
+    #[test]
+    fn test_msd_radix_sort() {
+        let mut words: Vec<String> = (0..2000)
+            .map(|i| format!("{:x}", (i * 7919) % 4999))
+            .chain(["", "a", "ab", "abc", "ab", "", "b"].map(String::from))
+            .collect();
+        let mut expected = words.clone();
+        expected.sort();
+        msd_radix_sort(&mut words);
+        assert_eq!(words, expected);
+
+        // A long shared prefix with binary data in it.
+        let prefix = vec![0u8; 5000];
+        let mut blobs: Vec<Vec<u8>> = (0..200u32)
+            .map(|i| {
+                let mut blob = prefix.clone();
+                blob.extend(((i * 37) % 101).to_be_bytes());
+                blob
+            })
+            .collect();
+        blobs.push(prefix[..10].to_vec());
+        let mut expected = blobs.clone();
+        expected.sort();
+        msd_radix_sort(&mut blobs);
+        assert_eq!(blobs, expected);
+    }
+
+    #[test]
+    fn test_counting_sort() {
+        let mut readings = [(3, "a"), (-2, "b"), (3, "c"), (0, "d"), (-2, "e"), (7, "f")];
+        counting_sort(&mut readings, |(bin, _)| *bin).unwrap();
+        assert_eq!(
+            readings,
+            [(-2, "b"), (-2, "e"), (0, "d"), (3, "a"), (3, "c"), (7, "f")]
+        );
+
+        let mut arr: Vec<u32> = (0..10_000).map(|i| (i * 7919) % 256).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+        counting_sort(&mut arr, |val| *val as i64).unwrap();
+        assert_eq!(arr, expected);
+
+        let mut arr = [i64::MAX, 0, i64::MIN];
+        let err = counting_sort(&mut arr, |val| *val).unwrap_err();
+        assert_eq!(err, SortError::RangeTooLarge { range: 1 << 64 });
+        assert_eq!(arr, [i64::MAX, 0, i64::MIN]);
+
+        let mut empty: [i64; 0] = [];
+        assert!(counting_sort(&mut empty, |val| *val).is_ok());
+    }
+
+    #[test]
+    fn test_bucket_sort() {
+        let mut arr: Vec<f64> = (0..10_000)
+            .map(|i| ((i * 7919) % 10_007) as f64 / 10_007.)
+            .collect();
+        let mut expected = arr.clone();
+        expected.sort_by(f64::total_cmp);
+        bucket_sort(&mut arr).unwrap();
+        assert_eq!(arr, expected);
+
+        let mut arr = [f64::MAX, -3.5, f64::MIN, 0.0, -3.5, 1e-300];
+        bucket_sort(&mut arr).unwrap();
+        assert_eq!(arr, [f64::MIN, -3.5, -3.5, 0.0, 1e-300, f64::MAX]);
+
+        // Signed zeros are ordered the way `total_cmp` orders them.
+        let bits = |arr: &[f64]| arr.iter().map(|val| val.to_bits()).collect::<Vec<_>>();
+        let mut arr = [0.0, -0.0];
+        bucket_sort(&mut arr).unwrap();
+        assert_eq!(bits(&arr), bits(&[-0.0, 0.0]));
+        let mut arr = [0.0, 1.0, -0.0, 0.0, -1.0, -0.0];
+        bucket_sort(&mut arr).unwrap();
+        assert_eq!(bits(&arr), bits(&[-1.0, -0.0, -0.0, 0.0, 0.0, 1.0]));
+
+        // Subnormals keep their values, however close together they are.
+        let tiny = 5e-324;
+        let mut arr = [tiny, 0.0];
+        bucket_sort(&mut arr).unwrap();
+        assert_eq!(bits(&arr), bits(&[0.0, tiny]));
+        let mut arr = [-tiny, tiny];
+        bucket_sort(&mut arr).unwrap();
+        assert_eq!(bits(&arr), bits(&[-tiny, tiny]));
+        let mut arr = [tiny, -tiny, tiny, -0.0];
+        bucket_sort(&mut arr).unwrap();
+        assert_eq!(bits(&arr), bits(&[-tiny, -0.0, tiny, tiny]));
+        let mut arr = [1.0, tiny, 1e-310, -tiny, 0.0, f64::MIN_POSITIVE];
+        bucket_sort(&mut arr).unwrap();
+        assert_eq!(
+            bits(&arr),
+            bits(&[-tiny, 0.0, tiny, 1e-310, f64::MIN_POSITIVE, 1.0])
+        );
+        let mut arr = [f64::MAX, tiny, 0.0, f64::MIN, -tiny];
+        bucket_sort(&mut arr).unwrap();
+        assert_eq!(bits(&arr), bits(&[f64::MIN, -tiny, 0.0, tiny, f64::MAX]));
+
+        let mut arr = [1.0, f64::NAN, 0.5];
+        assert_eq!(bucket_sort(&mut arr), Err(SortError::NotFinite));
+        assert_eq!(arr[0], 1.0);
+        assert!(bucket_sort(&mut [f64::INFINITY]).is_err());
+        assert!(bucket_sort(&mut []).is_ok());
+    }
+
+    #[test]
+    fn test_par_sort() {
+        let config = ParConfig {
+            threads: 5,
+            cutoff: 1000,
+        };
+        check_stable_sort(|arr, cmp| {
+            par_sort_by(
+                arr,
+                &ParConfig {
+                    threads: 3,
+                    cutoff: 50,
+                },
+                cmp,
+            )
+        });
+
+        let n = 100_000u64;
+        let inputs: Vec<Vec<u64>> = vec![
+            (0..n).map(|i| (i * 7919) % n).collect(),
+            (0..n).map(|i| i % 7).collect(),
+            (0..n).rev().collect(),
+            vec![5; n as usize],
+            vec![1],
+            Vec::new(),
+        ];
+        for input in inputs {
+            let mut expected = input.clone();
+            expected.sort();
+            for threads in [0, 1, 2, 5] {
+                let config = ParConfig { threads, ..config };
+                let mut arr = input.clone();
+                par_sort(&mut arr, &config);
+                assert_eq!(arr, expected);
+                let mut arr = input.clone();
+                par_quicksort(&mut arr, &config);
+                assert_eq!(arr, expected);
+            }
+        }
+
+        let mut words: Vec<String> = (0..5000).map(|i| format!("w{}", (i * 31) % 5000)).collect();
+        let mut expected = words.clone();
+        expected.sort_by(|a, b| b.cmp(a));
+        par_quicksort_by(&mut words, &config, |a, b| b.cmp(a));
+        assert_eq!(words, expected);
     }
 }
@@ -0,0 +1,186 @@
+//! Replays an access trace against each cache policy at several capacities
+//! and prints the hit ratios.
+//!
+//! Usage: cache-sim [--format plain|arc|lirs] [--capacities 100,1000,...] TRACE
+//!
+//! `plain` traces have one key per line. `arc` traces have lines of
+//! `start_block block_count ignored request_number`, each touching
+//! `block_count` consecutive blocks, at most 1M per line. `lirs` traces have
+//! one block number per line. Lines that don't parse are skipped and
+//! counted on stderr.
+//!
+//! The trace is streamed once, feeding every policy at every capacity, so
+//! it never has to fit in memory.
+
+use algo::cache::{CachePolicy, Fifo};
+use algo::lru::LRU;
+use std::env;
+use std::fmt::Write;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::process;
+
+#[derive(Clone, Copy)]
+enum Format {
+    Plain,
+    Arc,
+    Lirs,
+}
+
+struct Args {
+    format: Format,
+    capacities: Vec<usize>,
+    path: String,
+}
+
+fn usage() -> ! {
+    eprintln!("usage: cache-sim [--format plain|arc|lirs] [--capacities 100,1000,...] TRACE");
+    process::exit(2);
+}
+
+fn parse_args() -> Args {
+    let mut format = Format::Plain;
+    let mut capacities = vec![100, 1_000, 10_000];
+    let mut path = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().as_deref() {
+                    Some("plain") => Format::Plain,
+                    Some("arc") => Format::Arc,
+                    Some("lirs") => Format::Lirs,
+                    _ => usage(),
+                }
+            }
+            "--capacities" => {
+                let list = args.next().unwrap_or_else(|| usage());
+                capacities = list
+                    .split(',')
+                    .map(|capacity| capacity.trim().parse().unwrap_or_else(|_| usage()))
+                    .collect();
+            }
+            "-h" | "--help" => usage(),
+            _ if path.is_none() => path = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    Args {
+        format,
+        capacities,
+        path: path.unwrap_or_else(|| usage()),
+    }
+}
+
+/// The most blocks one `arc` line may touch. Real traces stay far below
+/// it; a corrupt line could otherwise replay for hours.
+const MAX_BLOCK_COUNT: u64 = 1 << 20;
+
+/// Calls `access` with each key of the trace in order, reading it line by
+/// line. Returns how many non-empty lines were skipped.
+fn replay_trace(
+    path: &str,
+    format: Format,
+    mut access: impl FnMut(&String),
+) -> std::io::Result<usize> {
+    let reader = BufReader::new(File::open(path)?);
+    // Reused for every access rather than allocated per block.
+    let mut key = String::new();
+    let mut skipped = 0;
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match format {
+            Format::Plain => {
+                key.clear();
+                key.push_str(line);
+                access(&key);
+            }
+            Format::Arc => {
+                let mut fields = line.split_whitespace().map(|field| field.parse::<u64>());
+                match (fields.next(), fields.next()) {
+                    (Some(Ok(start)), Some(Ok(count)))
+                        if count <= MAX_BLOCK_COUNT && start.checked_add(count).is_some() =>
+                    {
+                        for block in start..start + count {
+                            set_block(&mut key, block);
+                            access(&key);
+                        }
+                    }
+                    _ => skipped += 1,
+                }
+            }
+            Format::Lirs => match line.parse::<u64>() {
+                Ok(block) => {
+                    set_block(&mut key, block);
+                    access(&key);
+                }
+                Err(_) => skipped += 1,
+            },
+        }
+    }
+    Ok(skipped)
+}
+
+fn set_block(key: &mut String, block: u64) {
+    key.clear();
+    write!(key, "{block}").unwrap();
+}
+
+fn policies(capacity: usize) -> Vec<Box<dyn CachePolicy<String>>> {
+    vec![
+        Box::new(LRU::<String, ()>::new(capacity as i64)),
+        Box::new(Fifo::new(capacity)),
+    ]
+}
+
+fn main() {
+    let args = parse_args();
+    // One row per capacity, holding each policy with its hit count.
+    let mut rows: Vec<Vec<(Box<dyn CachePolicy<String>>, usize)>> = args
+        .capacities
+        .iter()
+        .map(|&capacity| policies(capacity).into_iter().map(|p| (p, 0)).collect())
+        .collect();
+    let mut accesses = 0;
+    let skipped = replay_trace(&args.path, args.format, |key| {
+        accesses += 1;
+        for (policy, hits) in rows.iter_mut().flatten() {
+            if policy.access(key) {
+                *hits += 1;
+            }
+        }
+    })
+    .unwrap_or_else(|err| {
+        eprintln!("cache-sim: {}: {}", args.path, err);
+        process::exit(1);
+    });
+    if skipped > 0 {
+        eprintln!("cache-sim: {}: skipped {} bad lines", args.path, skipped);
+    }
+    if accesses == 0 {
+        eprintln!("cache-sim: {}: trace has no accesses", args.path);
+        process::exit(1);
+    }
+    println!("{} accesses from {}", accesses, args.path);
+
+    let names: Vec<String> = policies(0).iter().map(|p| p.name().to_string()).collect();
+    print!("{:>10}", "capacity");
+    for name in &names {
+        print!("{:>10}", name);
+    }
+    println!();
+
+    for (capacity, row) in args.capacities.iter().zip(rows) {
+        print!("{:>10}", capacity);
+        for (_, hits) in row {
+            print!("{:>9.2}%", 100. * hits as f64 / accesses as f64);
+        }
+        println!();
+    }
+}
@@ -0,0 +1,124 @@
+//! Times the crate's unstable sorts against `slice::sort_unstable` on a few
+//! input patterns and prints the best of several runs in milliseconds.
+//!
+//! Usage: sort-bench [--len 1000000] [--runs 5]
+//!
+//! Build with `--release`; debug timings say little.
+
+use algo::arrays::{pdqsort, quicksort};
+use std::env;
+use std::process;
+use std::time::{Duration, Instant};
+
+struct Args {
+    len: usize,
+    runs: usize,
+}
+
+fn usage() -> ! {
+    eprintln!("usage: sort-bench [--len 1000000] [--runs 5]");
+    process::exit(2);
+}
+
+fn parse_args() -> Args {
+    let mut len = 1_000_000;
+    let mut runs = 5;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or_else(|| usage())
+        };
+        match arg.as_str() {
+            "--len" => len = value(),
+            "--runs" => runs = value(),
+            _ => usage(),
+        }
+    }
+    if runs == 0 {
+        usage();
+    }
+
+    Args { len, runs }
+}
+
+/// xorshift64, so the inputs are the same on every run without a
+/// dependency on `rand`.
+fn random(len: usize) -> Vec<u64> {
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    (0..len)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        })
+        .collect()
+}
+
+fn patterns(len: usize) -> Vec<(&'static str, Vec<u64>)> {
+    let sorted: Vec<u64> = (0..len as u64).collect();
+    let mut nearly_sorted = sorted.clone();
+    for (idx, val) in random(len / 100).into_iter().enumerate() {
+        nearly_sorted.swap(idx * 100, val as usize % len);
+    }
+    vec![
+        ("random", random(len)),
+        (
+            "few unique",
+            random(len).iter().map(|val| val % 16).collect(),
+        ),
+        ("sorted", sorted.clone()),
+        ("reversed", sorted.iter().rev().copied().collect()),
+        ("nearly sorted", nearly_sorted),
+        (
+            "sawtooth",
+            sorted
+                .iter()
+                .map(|val| val % (len as u64 / 10 + 1))
+                .collect(),
+        ),
+    ]
+}
+
+fn best_time(input: &[u64], runs: usize, sort: fn(&mut [u64])) -> Duration {
+    let mut expected = input.to_vec();
+    expected.sort_unstable();
+    (0..runs)
+        .map(|_| {
+            let mut arr = input.to_vec();
+            let start = Instant::now();
+            sort(&mut arr);
+            let elapsed = start.elapsed();
+            assert!(arr == expected, "sort produced the wrong order");
+            elapsed
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let args = parse_args();
+    let sorts: [(&str, fn(&mut [u64])); 3] = [
+        ("quicksort", quicksort),
+        ("pdqsort", pdqsort),
+        ("sort_unstable", <[u64]>::sort_unstable),
+    ];
+
+    println!("{} elements, best of {} runs (ms)", args.len, args.runs);
+    print!("{:<14}", "pattern");
+    for (name, _) in sorts.iter() {
+        print!("{name:>15}");
+    }
+    println!();
+    for (pattern, input) in patterns(args.len) {
+        print!("{pattern:<14}");
+        for (_, sort) in sorts.iter() {
+            let time = best_time(&input, args.runs, *sort);
+            print!("{:>15.2}", time.as_secs_f64() * 1000.);
+        }
+        println!();
+    }
+}
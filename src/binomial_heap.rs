@@ -0,0 +1,161 @@
+/// A binomial tree of order k: a root with children of orders 0 to k - 1.
+struct Tree<T> {
+    value: T,
+    children: Vec<Tree<T>>,
+}
+
+/// Makes the root with the larger value the last child of the other,
+/// turning two trees of order k into one of order k + 1.
+fn link<T: PartialOrd>(mut a: Tree<T>, mut b: Tree<T>) -> Tree<T> {
+    if b.value < a.value {
+        std::mem::swap(&mut a, &mut b);
+    }
+    a.children.push(b);
+    a
+}
+
+/// A min-heap kept as a forest with at most one binomial tree per order,
+/// like the bits of its length. Two heaps merge in O(log n) the same way two
+/// binary numbers are added.
+pub struct BinomialHeap<T> {
+    trees: Vec<Option<Tree<T>>>,
+    length: usize,
+}
+
+impl<T: PartialOrd> Default for BinomialHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialOrd> BinomialHeap<T> {
+    pub fn new() -> Self {
+        Self {
+            trees: Vec::new(),
+            length: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn push(&mut self, value: T) {
+        let tree = Tree {
+            value,
+            children: Vec::new(),
+        };
+        self.add_trees(vec![Some(tree)]);
+        self.length += 1;
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.min_order()
+            .map(|order| &self.trees[order].as_ref().unwrap().value)
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let order = self.min_order()?;
+        let tree = self.trees[order].take().unwrap();
+        // A tree's children are themselves a forest with one tree per order.
+        self.add_trees(tree.children.into_iter().map(Some).collect());
+        self.length -= 1;
+        Some(tree.value)
+    }
+
+    pub fn merge(&mut self, other: BinomialHeap<T>) {
+        self.length += other.length;
+        self.add_trees(other.trees);
+    }
+
+    fn min_order(&self) -> Option<usize> {
+        let mut min: Option<(usize, &T)> = None;
+        for (order, tree) in self.trees.iter().enumerate() {
+            if let Some(tree) = tree {
+                if min.is_none_or(|(_, value)| tree.value < *value) {
+                    min = Some((order, &tree.value));
+                }
+            }
+        }
+        min.map(|(order, _)| order)
+    }
+
+    /// Adds a forest indexed by order, carrying like binary addition.
+    fn add_trees(&mut self, others: Vec<Option<Tree<T>>>) {
+        let orders = self.trees.len().max(others.len()) + 1;
+        self.trees.resize_with(orders, || None);
+        let mut others = others.into_iter();
+        let mut carry = None;
+        for order in 0..orders {
+            let mut trees: Vec<Tree<T>> =
+                [self.trees[order].take(), others.next().flatten(), carry]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+            carry = if trees.len() >= 2 {
+                let a = trees.pop().unwrap();
+                let b = trees.pop().unwrap();
+                Some(link(a, b))
+            } else {
+                None
+            };
+            self.trees[order] = trees.pop();
+        }
+        while matches!(self.trees.last(), Some(None)) {
+            self.trees.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(heap: &mut BinomialHeap<i64>) -> Vec<i64> {
+        let mut out = Vec::new();
+        while let Some(val) = heap.pop() {
+            out.push(val);
+        }
+        out
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut heap = BinomialHeap::new();
+        let values: Vec<i64> = (0..100).map(|i| (i * 37) % 101).collect();
+        for val in values.iter() {
+            heap.push(*val);
+        }
+        assert_eq!(heap.len(), 100);
+        assert_eq!(heap.peek(), Some(&0));
+        // 100 is 0b1100100, so the forest has three trees.
+        assert_eq!(heap.trees.iter().flatten().count(), 3);
+
+        let mut sorted = values.clone();
+        sorted.sort();
+        assert_eq!(drain(&mut heap), sorted);
+        assert!(heap.is_empty());
+        assert_eq!(heap.peek(), None);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = BinomialHeap::new();
+        let mut b = BinomialHeap::new();
+        for val in [9, 3, 7] {
+            a.push(val);
+        }
+        for val in [8, 1, 4, 6, 2] {
+            b.push(val);
+        }
+        a.merge(b);
+        a.merge(BinomialHeap::new());
+        assert_eq!(a.len(), 8);
+        assert_eq!(a.trees.iter().flatten().count(), 1);
+        assert_eq!(drain(&mut a), vec![1, 2, 3, 4, 6, 7, 8, 9]);
+    }
+}
@@ -0,0 +1,383 @@
+use crate::lru::LRU;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Instant;
+
+/// Produces the value for a key that isn't cached yet.
+pub trait Loader<K, V> {
+    type Error;
+
+    fn load(&self, key: &K) -> Result<V, Self::Error>;
+}
+
+impl<K, V, E, F> Loader<K, V> for F
+where
+    F: Fn(&K) -> Result<V, E>,
+{
+    type Error = E;
+
+    fn load(&self, key: &K) -> Result<V, E> {
+        self(key)
+    }
+}
+
+/// An `LRU` that fills its own misses from a `Loader`.
+pub struct LoadingCache<K, V, L> {
+    cache: LRU<K, V>,
+    loader: L,
+}
+
+impl<K, V, L> LoadingCache<K, V, L>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    L: Loader<K, V>,
+{
+    pub fn new(capacity: i64, loader: L) -> Self {
+        Self {
+            cache: LRU::new(capacity),
+            loader,
+        }
+    }
+
+    /// Returns the cached value, loading and storing it on a miss. Failed
+    /// loads are returned as-is and leave nothing behind in the cache.
+    pub fn get(&mut self, key: &K) -> Result<V, L::Error> {
+        if let Some(value) = self.cache.get(key) {
+            return Ok(value);
+        }
+        let start = Instant::now();
+        let loaded = self.loader.load(key);
+        self.cache.record_load(start.elapsed());
+        let value = loaded?;
+        self.cache.put(key.clone(), value.clone());
+        Ok(value)
+    }
+
+    pub fn cache(&self) -> &LRU<K, V> {
+        &self.cache
+    }
+
+    pub fn cache_mut(&mut self) -> &mut LRU<K, V> {
+        &mut self.cache
+    }
+}
+
+enum FlightState<V, E> {
+    Loading,
+    Done(Result<V, E>),
+    Abandoned,
+}
+
+/// A load in progress that other threads missing on the same key wait on.
+struct Flight<V, E> {
+    state: Mutex<FlightState<V, E>>,
+    done: Condvar,
+}
+
+impl<V, E> Flight<V, E> {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(FlightState::Loading),
+            done: Condvar::new(),
+        }
+    }
+
+    fn finish(&self, state: FlightState<V, E>) {
+        *self.state.lock().unwrap() = state;
+        self.done.notify_all();
+    }
+}
+
+type InFlight<K, V, E> = Mutex<HashMap<K, Arc<Flight<V, E>>>>;
+
+/// Clears the in-flight entry even if the loader panics, so waiters can
+/// retry instead of blocking forever.
+struct FlightGuard<'a, K, V, E>
+where
+    K: Hash + Eq,
+{
+    key: &'a K,
+    flight: Arc<Flight<V, E>>,
+    in_flight: &'a InFlight<K, V, E>,
+    result: Option<Result<V, E>>,
+}
+
+impl<K, V, E> Drop for FlightGuard<'_, K, V, E>
+where
+    K: Hash + Eq,
+{
+    fn drop(&mut self) {
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            in_flight.remove(self.key);
+        }
+        match self.result.take() {
+            Some(result) => self.flight.finish(FlightState::Done(result)),
+            None => self.flight.finish(FlightState::Abandoned),
+        }
+    }
+}
+
+/// A thread-safe `LoadingCache`. Concurrent misses on the same key share a
+/// single call to the loader: the first thread loads, the rest wait for its
+/// result.
+pub struct SyncLoadingCache<K, V, L>
+where
+    L: Loader<K, V>,
+{
+    cache: Mutex<LRU<K, V>>,
+    in_flight: InFlight<K, V, L::Error>,
+    loader: L,
+}
+
+impl<K, V, L> SyncLoadingCache<K, V, L>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    L: Loader<K, V>,
+    L::Error: Clone,
+{
+    pub fn new(capacity: i64, loader: L) -> Self {
+        Self {
+            cache: Mutex::new(LRU::new(capacity)),
+            in_flight: Mutex::new(HashMap::new()),
+            loader,
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Result<V, L::Error> {
+        loop {
+            let flight = {
+                // The cache is checked under the in-flight lock so a load that
+                // finishes between the two checks can't be missed.
+                let mut in_flight = self.in_flight.lock().unwrap();
+                if let Some(flight) = in_flight.get(key) {
+                    flight.clone()
+                } else {
+                    if let Some(value) = self.cache.lock().unwrap().get(key) {
+                        return Ok(value);
+                    }
+                    let flight = Arc::new(Flight::new());
+                    in_flight.insert(key.clone(), flight.clone());
+                    drop(in_flight);
+                    return self.load(key, flight);
+                }
+            };
+
+            let mut state = flight.state.lock().unwrap();
+            while let FlightState::Loading = *state {
+                state = flight.done.wait(state).unwrap();
+            }
+            if let FlightState::Done(result) = &*state {
+                return result.clone();
+            }
+            // The loading thread panicked; try again, possibly as the loader.
+        }
+    }
+
+    /// Runs `f` with the underlying cache locked, e.g. to read its stats.
+    pub fn with_cache<R>(&self, f: impl FnOnce(&mut LRU<K, V>) -> R) -> R {
+        f(&mut self.cache.lock().unwrap())
+    }
+
+    fn load(&self, key: &K, flight: Arc<Flight<V, L::Error>>) -> Result<V, L::Error> {
+        let mut guard = FlightGuard {
+            key,
+            flight,
+            in_flight: &self.in_flight,
+            result: None,
+        };
+        let start = Instant::now();
+        let loaded = self.loader.load(key);
+        {
+            let mut cache = self.cache.lock().unwrap();
+            cache.record_load(start.elapsed());
+            if let Ok(value) = &loaded {
+                cache.put(key.clone(), value.clone());
+            }
+        }
+        guard.result = Some(loaded.clone());
+        loaded
+    }
+}
+
+/// A replacement policy that can be replayed against an access trace.
+pub trait CachePolicy<K> {
+    fn name(&self) -> &str;
+
+    /// Requests `key`, admitting it on a miss. Returns whether it was a hit.
+    fn access(&mut self, key: &K) -> bool;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn capacity(&self) -> usize;
+}
+
+impl<K, V> CachePolicy<K> for LRU<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone + Default,
+{
+    fn name(&self) -> &str {
+        "lru"
+    }
+
+    fn access(&mut self, key: &K) -> bool {
+        if self.get(key).is_some() {
+            return true;
+        }
+        self.put(key.clone(), V::default());
+        false
+    }
+
+    fn len(&self) -> usize {
+        LRU::len(self)
+    }
+
+    fn capacity(&self) -> usize {
+        LRU::capacity(self)
+    }
+}
+
+/// Evicts in insertion order and ignores hits. Mostly useful as a baseline.
+pub struct Fifo<K> {
+    queue: VecDeque<K>,
+    members: HashSet<K>,
+    capacity: usize,
+}
+
+impl<K> Fifo<K>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            members: HashSet::new(),
+            capacity,
+        }
+    }
+}
+
+impl<K> CachePolicy<K> for Fifo<K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn name(&self) -> &str {
+        "fifo"
+    }
+
+    fn access(&mut self, key: &K) -> bool {
+        if self.members.contains(key) {
+            return true;
+        }
+        if self.capacity == 0 {
+            return false;
+        }
+        if self.queue.len() == self.capacity {
+            let oldest = self.queue.pop_front().unwrap();
+            self.members.remove(&oldest);
+        }
+        self.queue.push_back(key.clone());
+        self.members.insert(key.clone());
+        false
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_loading_cache() {
+        let calls = Cell::new(0);
+        let mut cache = LoadingCache::new(2, |key: &i64| {
+            calls.set(calls.get() + 1);
+            if *key < 0 {
+                return Err(format!("no value for {}", key));
+            }
+            Ok(key * 10)
+        });
+        cache.cache_mut().record_stats();
+
+        assert_eq!(cache.get(&1), Ok(10));
+        assert_eq!(cache.get(&1), Ok(10));
+        assert_eq!(cache.get(&-1), Err("no value for -1".to_string()));
+        assert_eq!(cache.get(&-1), Err("no value for -1".to_string()));
+        assert_eq!(calls.get(), 3);
+
+        let stats = cache.cache().stats().unwrap();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 3);
+        assert_eq!(stats.inserts, 1);
+    }
+
+    #[test]
+    fn test_sync_loading_cache_single_flight() {
+        let calls = AtomicUsize::new(0);
+        let cache = SyncLoadingCache::new(10, |key: &u64| -> Result<u64, ()> {
+            calls.fetch_add(1, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(50));
+            Ok(key + 1)
+        });
+
+        thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| assert_eq!(cache.get(&41), Ok(42)));
+            }
+        });
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.get(&41), Ok(42));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_sync_loading_cache_retries_after_panic() {
+        let calls = AtomicUsize::new(0);
+        let cache = SyncLoadingCache::new(10, |key: &u64| -> Result<u64, ()> {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                panic!("backend down");
+            }
+            Ok(*key)
+        });
+
+        thread::scope(|s| {
+            assert!(s.spawn(|| cache.get(&7)).join().is_err());
+        });
+        assert_eq!(cache.get(&7), Ok(7));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    fn hits(policy: &mut dyn CachePolicy<u32>, trace: &[u32]) -> usize {
+        trace.iter().filter(|key| policy.access(key)).count()
+    }
+
+    #[test]
+    fn test_policies() {
+        let trace = [1, 2, 1, 3, 1, 4, 1];
+        let mut lru = LRU::<u32, ()>::new(2);
+        let mut fifo = Fifo::new(2);
+        assert_eq!(hits(&mut lru, &trace), 3);
+        assert_eq!(hits(&mut fifo, &trace), 2);
+        assert_eq!((lru.len(), CachePolicy::capacity(&lru)), (2, 2));
+        assert_eq!((fifo.len(), fifo.capacity()), (2, 2));
+        assert_eq!(hits(&mut Fifo::new(0), &trace), 0);
+    }
+}
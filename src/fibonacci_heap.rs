@@ -0,0 +1,377 @@
+use crate::heap_handle::{self, HeapId};
+use std::cell::{Ref, RefCell};
+use std::rc::{Rc, Weak};
+
+type Link<T> = Rc<RefCell<FibNode<T>>>;
+type WeakLink<T> = Weak<RefCell<FibNode<T>>>;
+
+/// A node of a `FibonacciHeap`. Only reachable through a `Handle`.
+pub struct FibNode<T> {
+    value: T,
+    parent: Option<WeakLink<T>>,
+    children: List<T>,
+    next: Option<Link<T>>,
+    prev: Option<WeakLink<T>>,
+    degree: usize,
+    // Set once the node has lost a child since it was last made a child.
+    marked: bool,
+}
+
+/// A doubly linked list of sibling nodes. Links run forwards as `Rc` and
+/// backwards as `Weak`, so the list never forms a reference cycle.
+struct List<T> {
+    head: Option<Link<T>>,
+    tail: Option<WeakLink<T>>,
+}
+
+impl<T> List<T> {
+    fn new() -> Self {
+        Self {
+            head: None,
+            tail: None,
+        }
+    }
+
+    fn push_front(&mut self, node: Link<T>) {
+        match self.head.take() {
+            Some(head) => {
+                head.borrow_mut().prev = Some(Rc::downgrade(&node));
+                node.borrow_mut().next = Some(head);
+            }
+            None => self.tail = Some(Rc::downgrade(&node)),
+        }
+        self.head = Some(node);
+    }
+
+    fn remove(&mut self, node: &Link<T>) {
+        let prev = node.borrow_mut().prev.take();
+        let prev = prev.and_then(|prev| prev.upgrade());
+        let next = node.borrow_mut().next.take();
+        match next.as_ref() {
+            Some(next) => next.borrow_mut().prev = prev.as_ref().map(Rc::downgrade),
+            None => self.tail = prev.as_ref().map(Rc::downgrade),
+        }
+        match prev {
+            Some(prev) => prev.borrow_mut().next = next,
+            None => self.head = next,
+        }
+    }
+
+    /// Moves every node of `other` to the end of this list in O(1).
+    fn append(&mut self, mut other: List<T>) {
+        let Some(other_head) = other.head.take() else {
+            return;
+        };
+        match self.tail.take().and_then(|tail| tail.upgrade()) {
+            Some(tail) => {
+                other_head.borrow_mut().prev = Some(Rc::downgrade(&tail));
+                tail.borrow_mut().next = Some(other_head);
+            }
+            None => self.head = Some(other_head),
+        }
+        self.tail = other.tail.take();
+    }
+
+    fn take_all(&mut self) -> Vec<Link<T>> {
+        let mut nodes = Vec::new();
+        let mut curr = self.head.take();
+        self.tail = None;
+        while let Some(node) = curr {
+            curr = node.borrow_mut().next.take();
+            node.borrow_mut().prev = None;
+            nodes.push(node);
+        }
+        nodes
+    }
+}
+
+pub type Handle<T> = heap_handle::Handle<FibNode<T>>;
+
+/// A min-heap with amortized O(1) `insert`, `meld` and `decrease_key` and
+/// O(log n) amortized `pop`. Work is put off until `pop`, which merges
+/// trees of equal degree; `decrease_key` cuts a node loose instead of
+/// sifting it up.
+pub struct FibonacciHeap<T> {
+    roots: List<T>,
+    min: Option<Link<T>>,
+    length: usize,
+    id: Rc<HeapId>,
+}
+
+impl<T: PartialOrd> Default for FibonacciHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialOrd> FibonacciHeap<T> {
+    pub fn new() -> Self {
+        Self {
+            roots: List::new(),
+            min: None,
+            length: 0,
+            id: HeapId::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn peek(&self) -> Option<Ref<'_, T>> {
+        self.min
+            .as_ref()
+            .map(|min| Ref::map(min.borrow(), |node| &node.value))
+    }
+
+    pub fn insert(&mut self, value: T) -> Handle<T> {
+        let node = Rc::new(RefCell::new(FibNode {
+            value,
+            parent: None,
+            children: List::new(),
+            next: None,
+            prev: None,
+            degree: 0,
+            marked: false,
+        }));
+        let handle = Handle::new(&node, &self.id);
+        self.roots.push_front(node.clone());
+        self.update_min(node);
+        self.length += 1;
+        handle
+    }
+
+    /// Splices the root list of `other` onto this one in O(1), leaving the
+    /// trees alone until the next `pop`. Handles from `other` then belong
+    /// to this heap.
+    pub fn meld(&mut self, mut other: FibonacciHeap<T>) {
+        other.id.forward(&self.id);
+        self.roots
+            .append(std::mem::replace(&mut other.roots, List::new()));
+        if let Some(min) = other.min.take() {
+            self.update_min(min);
+        }
+        self.length += other.length;
+        other.length = 0;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let min = self.min.take()?;
+        self.roots.remove(&min);
+        for child in min.borrow_mut().children.take_all() {
+            child.borrow_mut().parent = None;
+            self.roots.push_front(child);
+        }
+        self.length -= 1;
+        self.consolidate();
+        Some(heap_handle::into_node(min).value)
+    }
+
+    /// Lowers the item behind `handle` to `value`, cutting it to the root
+    /// list if it now beats its parent. Returns `false`, changing nothing,
+    /// if the handle is from another heap, its item was popped, or `value`
+    /// would raise it.
+    pub fn decrease_key(&mut self, handle: &Handle<T>, value: T) -> bool {
+        let Some(node) = handle.node_in(&self.id) else {
+            return false;
+        };
+        if value > node.borrow().value {
+            return false;
+        }
+        node.borrow_mut().value = value;
+
+        let parent = node.borrow().parent.as_ref().and_then(|p| p.upgrade());
+        if let Some(parent) = parent {
+            if node.borrow().value < parent.borrow().value {
+                self.cut(&node, &parent);
+                self.cascading_cut(parent);
+            }
+        }
+        self.update_min(node);
+        true
+    }
+
+    fn update_min(&mut self, node: Link<T>) {
+        let is_smaller = match self.min.as_ref() {
+            Some(min) => node.borrow().value < min.borrow().value,
+            None => true,
+        };
+        if is_smaller {
+            self.min = Some(node);
+        }
+    }
+
+    /// Moves `node` from under `parent` to the root list.
+    fn cut(&mut self, node: &Link<T>, parent: &Link<T>) {
+        {
+            let mut parent = parent.borrow_mut();
+            parent.children.remove(node);
+            parent.degree -= 1;
+        }
+        {
+            let mut node = node.borrow_mut();
+            node.parent = None;
+            node.marked = false;
+        }
+        self.roots.push_front(node.clone());
+    }
+
+    /// Cuts ancestors that have now lost two children, which is what keeps
+    /// tree sizes exponential in their degree.
+    fn cascading_cut(&mut self, mut node: Link<T>) {
+        loop {
+            let parent = node.borrow().parent.as_ref().and_then(|p| p.upgrade());
+            let Some(parent) = parent else {
+                return;
+            };
+            if !node.borrow().marked {
+                node.borrow_mut().marked = true;
+                return;
+            }
+            self.cut(&node, &parent);
+            node = parent;
+        }
+    }
+
+    /// Links roots of equal degree until every degree appears once, then
+    /// rebuilds the root list and finds the new minimum.
+    fn consolidate(&mut self) {
+        let mut by_degree: Vec<Option<Link<T>>> = Vec::new();
+        for mut tree in self.roots.take_all() {
+            let mut degree = tree.borrow().degree;
+            loop {
+                if degree >= by_degree.len() {
+                    by_degree.resize(degree + 1, None);
+                }
+                let Some(mut other) = by_degree[degree].take() else {
+                    break;
+                };
+                if other.borrow().value < tree.borrow().value {
+                    std::mem::swap(&mut tree, &mut other);
+                }
+                other.borrow_mut().parent = Some(Rc::downgrade(&tree));
+                other.borrow_mut().marked = false;
+                let mut parent = tree.borrow_mut();
+                parent.children.push_front(other);
+                parent.degree += 1;
+                degree += 1;
+            }
+            by_degree[degree] = Some(tree);
+        }
+
+        for tree in by_degree.into_iter().flatten() {
+            self.roots.push_front(tree.clone());
+            self.update_min(tree);
+        }
+    }
+}
+
+impl<T> Drop for FibonacciHeap<T> {
+    // Dropping the lists recursively could overflow the stack, so take the
+    // forest apart one node at a time.
+    fn drop(&mut self) {
+        self.min = None;
+        let mut stack = self.roots.take_all();
+        while let Some(node) = stack.pop() {
+            stack.extend(node.borrow_mut().children.take_all());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(heap: &mut FibonacciHeap<i64>) -> Vec<i64> {
+        let mut out = Vec::new();
+        while let Some(val) = heap.pop() {
+            out.push(val);
+        }
+        out
+    }
+
+    #[test]
+    fn test_insert_pop() {
+        let mut heap = FibonacciHeap::new();
+        let values: Vec<i64> = (0..500).map(|i| (i * 7919) % 503).collect();
+        for val in values.iter() {
+            heap.insert(*val);
+        }
+        assert_eq!(heap.len(), 500);
+        assert_eq!(*heap.peek().unwrap(), 0);
+
+        let mut sorted = values.clone();
+        sorted.sort();
+        assert_eq!(drain(&mut heap), sorted);
+        assert!(heap.is_empty());
+        assert!(heap.peek().is_none());
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a = FibonacciHeap::new();
+        let mut b = FibonacciHeap::new();
+        a.insert(5);
+        a.insert(3);
+        let handle = b.insert(8);
+        b.insert(4);
+        a.meld(b);
+        a.meld(FibonacciHeap::new());
+        assert_eq!(a.len(), 4);
+        assert!(a.decrease_key(&handle, 1));
+        assert_eq!(drain(&mut a), vec![1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_decrease_key_cascades() {
+        let mut heap = FibonacciHeap::new();
+        let handles: Vec<_> = (0..64).map(|val| heap.insert(val * 10)).collect();
+        // Popping once consolidates the roots into deep trees.
+        assert_eq!(heap.pop(), Some(0));
+
+        for (idx, val) in [(63, 5), (62, 4), (40, 3), (41, 2), (33, 1)] {
+            assert!(heap.decrease_key(&handles[idx], val));
+            assert_eq!(*heap.peek().unwrap(), val);
+        }
+        assert!(!heap.decrease_key(&handles[10], 1000));
+        assert!(!heap.decrease_key(&handles[0], -1));
+
+        let mut expected: Vec<i64> = (1..64)
+            .filter(|i| ![63, 62, 40, 41, 33].contains(i))
+            .map(|i| i * 10)
+            .collect();
+        expected.splice(0..0, [1, 2, 3, 4, 5]);
+        assert_eq!(drain(&mut heap), expected);
+    }
+
+    #[test]
+    fn test_foreign_handle() {
+        let mut a = FibonacciHeap::new();
+        let mut b = FibonacciHeap::new();
+        let from_a = a.insert(4);
+        let from_b = b.insert(7);
+        b.insert(2);
+        assert!(!a.decrease_key(&from_b, 0));
+        assert!(!b.decrease_key(&from_a, 0));
+        assert_eq!(*a.peek().unwrap(), 4);
+        assert_eq!(*b.peek().unwrap(), 2);
+
+        a.meld(b);
+        assert!(a.decrease_key(&from_b, 1));
+        assert!(!FibonacciHeap::new().decrease_key(&from_a, 0));
+        assert_eq!(drain(&mut a), vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn test_drop_large_heap() {
+        let mut heap = FibonacciHeap::new();
+        for val in 0..200_000 {
+            heap.insert(val);
+        }
+        heap.pop();
+    }
+}
@@ -1,3 +1,5 @@
+use crate::fibonacci_heap::{self, FibonacciHeap};
+use crate::heap::IndexedMinHeap;
 use std::collections::VecDeque;
 
 type WeightedAdjacenyMatrix = Vec<Vec<f64>>;
@@ -74,28 +76,85 @@ pub fn depth_first_search(
     false
 }
 
-// TODO: use a min heap for sorting of shortest path
+/// A min-priority queue of vertices ordered by distance, so the shortest
+/// path and spanning tree searches can run on any heap with decrease-key.
+pub trait VertexQueue {
+    fn with_vertices(count: usize) -> Self;
+    /// Queues `vertex`, or lowers its distance if it is already queued. A
+    /// queued vertex keeps its distance if `dist` isn't lower.
+    fn push_or_decrease(&mut self, vertex: usize, dist: f64);
+    fn pop(&mut self) -> Option<(usize, f64)>;
+}
+
+impl VertexQueue for IndexedMinHeap<usize, f64> {
+    fn with_vertices(_count: usize) -> Self {
+        IndexedMinHeap::new()
+    }
+
+    fn push_or_decrease(&mut self, vertex: usize, dist: f64) {
+        if !self.decrease_key(&vertex, dist) {
+            self.push(vertex, dist);
+        }
+    }
+
+    fn pop(&mut self) -> Option<(usize, f64)> {
+        IndexedMinHeap::pop(self)
+    }
+}
+
+/// Runs the graph searches on a `FibonacciHeap`, whose O(1) decrease-key
+/// pays off on dense graphs.
+pub struct FibonacciQueue {
+    heap: FibonacciHeap<(f64, usize)>,
+    handles: Vec<Option<fibonacci_heap::Handle<(f64, usize)>>>,
+}
+
+impl VertexQueue for FibonacciQueue {
+    fn with_vertices(count: usize) -> Self {
+        Self {
+            heap: FibonacciHeap::new(),
+            handles: vec![None; count],
+        }
+    }
+
+    fn push_or_decrease(&mut self, vertex: usize, dist: f64) {
+        match &self.handles[vertex] {
+            Some(handle) => {
+                self.heap.decrease_key(handle, (dist, vertex));
+            }
+            None => self.handles[vertex] = Some(self.heap.insert((dist, vertex))),
+        }
+    }
+
+    fn pop(&mut self) -> Option<(usize, f64)> {
+        let (dist, vertex) = self.heap.pop()?;
+        self.handles[vertex] = None;
+        Some((vertex, dist))
+    }
+}
+
 pub fn djikstras_shortest_path(g: &AdjacencyList, source: usize, target: usize) -> Vec<usize> {
-    let mut seen = vec![false; g.len()];
+    djikstras_shortest_path_with::<IndexedMinHeap<usize, f64>>(g, source, target)
+}
+
+pub fn djikstras_shortest_path_with<Q: VertexQueue>(
+    g: &AdjacencyList,
+    source: usize,
+    target: usize,
+) -> Vec<usize> {
     let mut dists = vec![f64::INFINITY; g.len()];
-    let mut prev = vec![-1 as i64; g.len()];
+    let mut prev = vec![-1_i64; g.len()];
+    let mut queue = Q::with_vertices(g.len());
     dists[source] = 0.;
+    queue.push_or_decrease(source, 0.);
 
-    println!("Beginning loop");
-    while has_unvisited(&seen, &dists) {
-        let curr = get_lowest_unvisited(&seen, &dists);
-        seen[curr] = true;
-
-        let adjs = &g[curr];
-        for edge in adjs {
-            if seen[edge.to] {
-                continue;
-            }
-            let dist = dists[curr] + edge.weight;
-            if dist < dists[edge.to] {
-                println!("path to {} from {} is shorest", edge.to, curr);
-                dists[edge.to] = dist;
+    while let Some((curr, dist)) = queue.pop() {
+        for edge in &g[curr] {
+            let next_dist = dist + edge.weight;
+            if next_dist < dists[edge.to] {
+                dists[edge.to] = next_dist;
                 prev[edge.to] = curr as i64;
+                queue.push_or_decrease(edge.to, next_dist);
             }
         }
     }
@@ -111,6 +170,33 @@ pub fn djikstras_shortest_path(g: &AdjacencyList, source: usize, target: usize)
     return out;
 }
 
+/// Grows a minimum spanning tree from `root` and returns each vertex's
+/// parent in it. `g` must list every edge in both directions. Vertices
+/// that can't be reached from `root` have no parent.
+pub fn prims_minimum_spanning_tree<Q: VertexQueue>(
+    g: &AdjacencyList,
+    root: usize,
+) -> Vec<Option<usize>> {
+    let mut in_tree = vec![false; g.len()];
+    let mut costs = vec![f64::INFINITY; g.len()];
+    let mut parents = vec![None; g.len()];
+    let mut queue = Q::with_vertices(g.len());
+    costs[root] = 0.;
+    queue.push_or_decrease(root, 0.);
+
+    while let Some((curr, _)) = queue.pop() {
+        in_tree[curr] = true;
+        for edge in &g[curr] {
+            if !in_tree[edge.to] && edge.weight < costs[edge.to] {
+                costs[edge.to] = edge.weight;
+                parents[edge.to] = Some(curr);
+                queue.push_or_decrease(edge.to, edge.weight);
+            }
+        }
+    }
+    parents
+}
+
 pub fn has_unvisited(seen: &Vec<bool>, dists: &Vec<f64>) -> bool {
     for (i, e) in seen.iter().enumerate() {
         if !e && dists[i] < f64::INFINITY {
@@ -159,6 +245,102 @@ mod tests {
            
     }
 
+    type BinaryQueue = IndexedMinHeap<usize, f64>;
+
+    fn dense_graph(size: usize) -> AdjacencyList {
+        (0..size)
+            .map(|from| {
+                (0..size)
+                    .filter(|to| *to != from)
+                    .map(|to| Node {
+                        to,
+                        weight: ((from * 31 + to * 17) % 23) as f64 + 1.,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_djikstra_queues_agree() {
+        let g = dense_graph(40);
+        // Ties can pick different paths, so compare what the paths cost.
+        let cost = |path: Vec<usize>| -> f64 {
+            path.windows(2)
+                .map(|hop| g[hop[0]].iter().find(|e| e.to == hop[1]).unwrap().weight)
+                .sum()
+        };
+        for target in 1..g.len() {
+            assert_eq!(
+                cost(djikstras_shortest_path(&g, 0, target)),
+                cost(djikstras_shortest_path_with::<FibonacciQueue>(
+                    &g, 0, target
+                ))
+            );
+        }
+    }
+
+    fn drain<Q: VertexQueue>(mut queue: Q) -> Vec<(usize, f64)> {
+        std::iter::from_fn(|| queue.pop()).collect()
+    }
+
+    fn check_queue<Q: VertexQueue>() {
+        let mut queue = Q::with_vertices(4);
+        queue.push_or_decrease(1, 1.);
+        queue.push_or_decrease(1, 5.);
+        queue.push_or_decrease(2, 4.);
+        queue.push_or_decrease(2, 3.);
+        queue.push_or_decrease(3, 2.);
+        assert_eq!(drain(queue), vec![(1, 1.), (3, 2.), (2, 3.)]);
+
+        // A popped vertex can be queued again.
+        let mut queue = Q::with_vertices(2);
+        queue.push_or_decrease(0, 1.);
+        assert_eq!(queue.pop(), Some((0, 1.)));
+        queue.push_or_decrease(0, 2.);
+        assert_eq!(drain(queue), vec![(0, 2.)]);
+    }
+
+    #[test]
+    fn test_vertex_queues() {
+        check_queue::<BinaryQueue>();
+        check_queue::<FibonacciQueue>();
+    }
+
+    #[test]
+    fn test_prims() {
+        let edges = [
+            (0, 1, 4.),
+            (0, 2, 1.),
+            (1, 2, 2.),
+            (1, 3, 5.),
+            (2, 3, 8.),
+            (3, 4, 3.),
+        ];
+        let mut g: AdjacencyList = vec![vec![]; 6];
+        for (from, to, weight) in edges {
+            g[from].push(Node { to, weight });
+            g[to].push(Node { to: from, weight });
+        }
+        let parents = vec![None, Some(2), Some(0), Some(1), Some(3), None];
+        assert_eq!(prims_minimum_spanning_tree::<BinaryQueue>(&g, 0), parents);
+        assert_eq!(
+            prims_minimum_spanning_tree::<FibonacciQueue>(&g, 0),
+            parents
+        );
+
+        let g = dense_graph(30);
+        let weight = |from: usize, to: usize| g[from].iter().find(|e| e.to == to).unwrap().weight;
+        let total = |parents: Vec<Option<usize>>| -> f64 {
+            let edges = parents.into_iter().enumerate();
+            edges.filter_map(|(to, from)| Some(weight(from?, to))).sum()
+        };
+        assert_eq!(
+            total(prims_minimum_spanning_tree::<BinaryQueue>(&g, 0)),
+            total(prims_minimum_spanning_tree::<FibonacciQueue>(&g, 0))
+        );
+    }
+
     #[test]
     fn test_bfs() {
         let mut m = vec![vec![0.; 4]; 4];
@@ -1,36 +1,178 @@
-pub struct MinHeap<T> {
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Decides which of two items a heap gives out first.
+pub trait Compare<T> {
+    /// `Less` when `a` should come out before `b`.
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+/// Smallest first. Incomparable values (e.g. NaN) are treated as equal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MinOrder;
+
+/// Largest first. Incomparable values (e.g. NaN) are treated as equal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MaxOrder;
+
+/// Smallest key first, where the key is computed from each item.
+#[derive(Debug, Clone, Copy)]
+pub struct ByKey<F>(pub F);
+
+impl<T: PartialOrd> Compare<T> for MinOrder {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.partial_cmp(b).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl<T: PartialOrd> Compare<T> for MaxOrder {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        b.partial_cmp(a).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl<T, K, F> Compare<T> for ByKey<F>
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a)
+            .partial_cmp(&(self.0)(b))
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl<T, F> Compare<T> for F
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// An array-backed binary heap that gives out whichever item `C` orders
+/// first. Items are only ever moved or swapped, never copied.
+pub struct BinaryHeap<T, C> {
     pub length: usize,
     pub data: Vec<T>,
+    cmp: C,
 }
 
-impl<T> MinHeap<T>
+pub type MinHeap<T> = BinaryHeap<T, MinOrder>;
+pub type MaxHeap<T> = BinaryHeap<T, MaxOrder>;
+
+impl<T, C> BinaryHeap<T, C>
 where
-    T: Copy + PartialOrd,
+    C: Compare<T> + Default,
 {
-    pub fn new(&mut self) {
-        self.length = 0;
-        self.data = Vec::new();
+    pub fn new() -> Self {
+        Self::with_comparator(C::default())
+    }
+
+    /// Builds a heap from `data` in O(n) by sifting down every parent,
+    /// starting from the last one.
+    pub fn from_vec(data: Vec<T>) -> Self {
+        Self::from_vec_with_comparator(data, C::default())
+    }
+}
+
+impl<T, C> Default for BinaryHeap<T, C>
+where
+    C: Compare<T> + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, K, F> BinaryHeap<T, ByKey<F>>
+where
+    K: PartialOrd,
+    F: Fn(&T) -> K,
+{
+    pub fn by_key(key: F) -> Self {
+        Self::with_comparator(ByKey(key))
+    }
+}
+
+impl<T, C> BinaryHeap<T, C>
+where
+    C: Compare<T>,
+{
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            length: 0,
+            data: Vec::new(),
+            cmp,
+        }
+    }
+
+    pub fn from_vec_with_comparator(data: Vec<T>, cmp: C) -> Self {
+        let mut heap = Self {
+            length: data.len(),
+            data,
+            cmp,
+        };
+        for idx in (0..heap.length / 2).rev() {
+            heap.heapify_down(idx);
+        }
+        heap
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
     }
 
     pub fn insert(&mut self, value: T) {
-        self.data.insert(self.length, value);
+        self.data.push(value);
         self.heapify_up(self.length);
         self.length += 1;
     }
 
     pub fn delete(&mut self) -> T {
-        let val = self.data[0];
-
-        if self.length == 1 {
-            self.data = Vec::new();
-            self.length = 0;
-            return val;
+        if self.length == 0 {
+            panic!("Cannot delete from empty heap");
         }
-
-        self.data[0] = self.data[self.length - 1];
+        let val = self.data.swap_remove(0);
         self.length -= 1;
         self.heapify_down(0);
-        return val;
+        val
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.length == 0 {
+            return None;
+        }
+        Some(self.delete())
+    }
+
+    /// Returns every item in the order the heap would give them out, sorting
+    /// in place without allocating.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        while self.length > 1 {
+            self.length -= 1;
+            self.data.swap(0, self.length);
+            self.heapify_down(0);
+        }
+        self.data.reverse();
+        self.data
+    }
+
+    /// Removes items in priority order. Whatever isn't consumed is dropped
+    /// along with the iterator, leaving the heap empty.
+    pub fn drain(&mut self) -> Drain<'_, T, C> {
+        Drain { heap: self }
     }
 
     fn heapify_up(&mut self, idx: usize) {
@@ -38,44 +180,384 @@ where
             return;
         }
         let parent_idx = self.parent(idx);
-        let parent_val = self.data[parent_idx];
-        let curr_val = self.data[idx];
+        if self.precedes(idx, parent_idx) {
+            self.data.swap(idx, parent_idx);
+            self.heapify_up(parent_idx);
+        }
+    }
+
+    fn heapify_down(&mut self, idx: usize) {
+        let left_idx = self.left_child(idx);
+        let right_idx = self.right_child(idx);
+
+        if left_idx >= self.length {
+            return;
+        }
+
+        let min_idx = if right_idx < self.length && self.precedes(right_idx, left_idx) {
+            right_idx
+        } else {
+            left_idx
+        };
+        if self.precedes(min_idx, idx) {
+            self.data.swap(min_idx, idx);
+            self.heapify_down(min_idx);
+        }
+    }
+
+    fn precedes(&self, a: usize, b: usize) -> bool {
+        self.cmp.compare(&self.data[a], &self.data[b]) == Ordering::Less
+    }
+
+    fn parent(&self, idx: usize) -> usize {
+        (idx - 1) / 2
+    }
 
-        if parent_val > curr_val {
-            self.data[parent_idx] = curr_val;
-            self.data[idx] = parent_val;
+    fn left_child(&self, idx: usize) -> usize {
+        idx * 2 + 1
+    }
+
+    fn right_child(&self, idx: usize) -> usize {
+        idx * 2 + 2
+    }
+}
+
+pub struct Drain<'a, T, C>
+where
+    C: Compare<T>,
+{
+    heap: &'a mut BinaryHeap<T, C>,
+}
+
+impl<T, C> Iterator for Drain<'_, T, C>
+where
+    C: Compare<T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.heap.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.length, Some(self.heap.length))
+    }
+}
+
+impl<T, C> Drop for Drain<'_, T, C>
+where
+    C: Compare<T>,
+{
+    fn drop(&mut self) {
+        self.heap.data.clear();
+        self.heap.length = 0;
+    }
+}
+
+/// Yields items in priority order.
+pub struct IntoIter<T, C> {
+    heap: BinaryHeap<T, C>,
+}
+
+impl<T, C> Iterator for IntoIter<T, C>
+where
+    C: Compare<T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.heap.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.length, Some(self.heap.length))
+    }
+}
+
+impl<T, C> IntoIterator for BinaryHeap<T, C>
+where
+    C: Compare<T>,
+{
+    type Item = T;
+    type IntoIter = IntoIter<T, C>;
+
+    fn into_iter(self) -> IntoIter<T, C> {
+        IntoIter { heap: self }
+    }
+}
+
+/// A heap where every node has `D` children instead of two. Wider nodes
+/// make the tree shallower, so inserts and decreases do fewer swaps, and a
+/// node's children sit next to each other in memory.
+pub struct DaryHeap<T, const D: usize, C = MinOrder> {
+    data: Vec<T>,
+    cmp: C,
+}
+
+impl<T, const D: usize, C> DaryHeap<T, D, C>
+where
+    C: Compare<T> + Default,
+{
+    pub fn new() -> Self {
+        Self::with_comparator(C::default())
+    }
+
+    pub fn from_vec(data: Vec<T>) -> Self {
+        let mut heap = Self::with_comparator(C::default());
+        heap.data = data;
+        if heap.data.len() > 1 {
+            for idx in (0..=heap.parent(heap.data.len() - 1)).rev() {
+                heap.heapify_down(idx);
+            }
+        }
+        heap
+    }
+}
+
+impl<T, const D: usize, C> Default for DaryHeap<T, D, C>
+where
+    C: Compare<T> + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const D: usize, C> DaryHeap<T, D, C>
+where
+    C: Compare<T>,
+{
+    pub fn with_comparator(cmp: C) -> Self {
+        assert!(D >= 2, "A d-ary heap needs at least two children per node");
+        Self {
+            data: Vec::new(),
+            cmp,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    pub fn insert(&mut self, value: T) {
+        self.data.push(value);
+        self.heapify_up(self.data.len() - 1);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let val = self.data.swap_remove(0);
+        self.heapify_down(0);
+        Some(val)
+    }
+
+    fn heapify_up(&mut self, idx: usize) {
+        if idx == 0 {
+            return;
+        }
+        let parent_idx = self.parent(idx);
+        if self.precedes(idx, parent_idx) {
+            self.data.swap(idx, parent_idx);
             self.heapify_up(parent_idx);
         }
     }
 
     fn heapify_down(&mut self, idx: usize) {
-        if idx >= self.length {
+        let first_idx = self.child(idx, 0);
+        if first_idx >= self.data.len() {
             return;
         }
+        let last_idx = self.child(idx, D - 1).min(self.data.len() - 1);
+        let mut min_idx = first_idx;
+        for child_idx in first_idx + 1..=last_idx {
+            if self.precedes(child_idx, min_idx) {
+                min_idx = child_idx;
+            }
+        }
+        if self.precedes(min_idx, idx) {
+            self.data.swap(min_idx, idx);
+            self.heapify_down(min_idx);
+        }
+    }
 
-        let left_idx = self.left_child(idx);
-        let right_idx = self.right_child(idx);
+    fn precedes(&self, a: usize, b: usize) -> bool {
+        self.cmp.compare(&self.data[a], &self.data[b]) == Ordering::Less
+    }
+
+    fn parent(&self, idx: usize) -> usize {
+        (idx - 1) / D
+    }
+
+    /// Index of the `nth` child of `idx`, counting from zero.
+    fn child(&self, idx: usize, nth: usize) -> usize {
+        idx * D + 1 + nth
+    }
+}
+
+/// A double-ended priority queue on one array. Even levels (starting with
+/// the root) are min levels, where each item is no larger than anything
+/// below it; odd levels are max levels, the other way around. The minimum
+/// is the root and the maximum is one of its children.
+pub struct MinMaxHeap<T> {
+    data: Vec<T>,
+}
+
+impl<T: PartialOrd> Default for MinMaxHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialOrd> MinMaxHeap<T> {
+    pub fn new() -> Self {
+        Self { data: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn peek_min(&self) -> Option<&T> {
+        self.data.first()
+    }
 
-        if left_idx > self.length || right_idx > self.length {
+    pub fn peek_max(&self) -> Option<&T> {
+        self.max_idx().map(|idx| &self.data[idx])
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.data.push(value);
+        let idx = self.data.len() - 1;
+        if idx == 0 {
             return;
         }
+        let parent_idx = self.parent(idx);
+        let on_min_level = self.is_min_level(idx);
+        // An item that belongs on the other kind of level swaps with its
+        // parent first, then climbs among grandparents only.
+        if self.ordered(idx, parent_idx, !on_min_level) {
+            self.data.swap(idx, parent_idx);
+            self.bubble_up(parent_idx, !on_min_level);
+        } else {
+            self.bubble_up(idx, on_min_level);
+        }
+    }
 
-        let right_val = self.data[right_idx];
-        let left_val = self.data[left_idx];
-        let curr_val = self.data[idx];
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.remove_at(0)
+    }
 
-        let (min_val, min_idx) = if left_val < right_val {
-            (left_val, left_idx)
+    pub fn pop_max(&mut self) -> Option<T> {
+        let idx = self.max_idx()?;
+        self.remove_at(idx)
+    }
+
+    fn max_idx(&self) -> Option<usize> {
+        match self.data.len() {
+            0 => None,
+            1 => Some(0),
+            2 => Some(1),
+            _ if self.ordered(2, 1, false) => Some(2),
+            _ => Some(1),
+        }
+    }
+
+    fn remove_at(&mut self, idx: usize) -> Option<T> {
+        if idx >= self.data.len() {
+            return None;
+        }
+        let val = self.data.swap_remove(idx);
+        if idx < self.data.len() {
+            self.trickle_down(idx);
+        }
+        Some(val)
+    }
+
+    fn bubble_up(&mut self, mut idx: usize, min: bool) {
+        while idx >= 3 {
+            let grandparent_idx = self.parent(self.parent(idx));
+            if !self.ordered(idx, grandparent_idx, min) {
+                return;
+            }
+            self.data.swap(idx, grandparent_idx);
+            idx = grandparent_idx;
+        }
+    }
+
+    fn trickle_down(&mut self, mut idx: usize) {
+        let min = self.is_min_level(idx);
+        loop {
+            let Some(best_idx) = self.best_descendant(idx, min) else {
+                return;
+            };
+            if !self.ordered(best_idx, idx, min) {
+                return;
+            }
+            self.data.swap(best_idx, idx);
+            if best_idx <= self.right_child(idx) {
+                return;
+            }
+            // A grandchild moved down past a level of the other kind, which
+            // it may now violate.
+            let parent_idx = self.parent(best_idx);
+            if self.ordered(parent_idx, best_idx, min) {
+                self.data.swap(best_idx, parent_idx);
+            }
+            idx = best_idx;
+        }
+    }
+
+    /// The smallest (or largest) of `idx`'s children and grandchildren.
+    fn best_descendant(&self, idx: usize, min: bool) -> Option<usize> {
+        let left_idx = self.left_child(idx);
+        let candidates = [
+            left_idx,
+            left_idx + 1,
+            self.left_child(left_idx),
+            self.left_child(left_idx) + 1,
+            self.left_child(left_idx + 1),
+            self.left_child(left_idx + 1) + 1,
+        ];
+        candidates
+            .into_iter()
+            .filter(|&candidate| candidate < self.data.len())
+            .reduce(|best, candidate| {
+                if self.ordered(candidate, best, min) {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// Whether the item at `a` belongs above the one at `b`: smaller on a
+    /// min level, larger on a max level.
+    fn ordered(&self, a: usize, b: usize, min: bool) -> bool {
+        if min {
+            self.data[a] < self.data[b]
         } else {
-            (right_val, right_idx)
-        };
-        if min_val < curr_val {
-            self.data[min_idx] = curr_val;
-            self.data[idx] = min_val;
-            self.heapify_down(min_idx);
+            self.data[a] > self.data[b]
         }
     }
 
+    fn is_min_level(&self, idx: usize) -> bool {
+        (idx + 1).ilog2().is_multiple_of(2)
+    }
+
     fn parent(&self, idx: usize) -> usize {
         (idx - 1) / 2
     }
@@ -89,6 +571,156 @@ where
     }
 }
 
+/// A min-heap of keys ordered by priority that remembers where each key
+/// sits, so a key's priority can be changed or the key removed in O(log n)
+/// instead of pushing duplicates and skipping stale entries on pop.
+pub struct IndexedMinHeap<K, P> {
+    data: Vec<(K, P)>,
+    positions: HashMap<K, usize>,
+}
+
+impl<K, P> Default for IndexedMinHeap<K, P>
+where
+    K: Hash + Eq + Clone,
+    P: PartialOrd,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, P> IndexedMinHeap<K, P>
+where
+    K: Hash + Eq + Clone,
+    P: PartialOrd,
+{
+    pub fn new() -> Self {
+        Self {
+            data: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    pub fn priority(&self, key: &K) -> Option<&P> {
+        self.positions.get(key).map(|&idx| &self.data[idx].1)
+    }
+
+    pub fn peek(&self) -> Option<(&K, &P)> {
+        self.data.first().map(|(key, priority)| (key, priority))
+    }
+
+    /// Adds `key`, or returns `false` without changing anything if it is
+    /// already queued.
+    pub fn push(&mut self, key: K, priority: P) -> bool {
+        if self.positions.contains_key(&key) {
+            return false;
+        }
+        let idx = self.data.len();
+        self.positions.insert(key.clone(), idx);
+        self.data.push((key, priority));
+        self.heapify_up(idx);
+        true
+    }
+
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        if self.data.is_empty() {
+            return None;
+        }
+        Some(self.remove_at(0))
+    }
+
+    /// Lowers the priority of `key`. Returns `false` if the key isn't queued
+    /// or `priority` isn't lower than its current one.
+    pub fn decrease_key(&mut self, key: &K, priority: P) -> bool {
+        match self.positions.get(key) {
+            Some(&idx) if priority < self.data[idx].1 => {
+                self.data[idx].1 = priority;
+                self.heapify_up(idx);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Raises the priority of `key`. Returns `false` if the key isn't queued
+    /// or `priority` isn't higher than its current one.
+    pub fn increase_key(&mut self, key: &K, priority: P) -> bool {
+        match self.positions.get(key) {
+            Some(&idx) if priority > self.data[idx].1 => {
+                self.data[idx].1 = priority;
+                self.heapify_down(idx);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<P> {
+        let idx = *self.positions.get(key)?;
+        Some(self.remove_at(idx).1)
+    }
+
+    fn remove_at(&mut self, idx: usize) -> (K, P) {
+        let last = self.data.len() - 1;
+        self.swap(idx, last);
+        let (key, priority) = self.data.pop().unwrap();
+        self.positions.remove(&key);
+        if idx < self.data.len() {
+            // The moved item may belong above or below its new slot.
+            self.heapify_up(idx);
+            self.heapify_down(idx);
+        }
+        (key, priority)
+    }
+
+    fn heapify_up(&mut self, idx: usize) {
+        if idx == 0 {
+            return;
+        }
+        let parent_idx = (idx - 1) / 2;
+        if self.data[idx].1 < self.data[parent_idx].1 {
+            self.swap(idx, parent_idx);
+            self.heapify_up(parent_idx);
+        }
+    }
+
+    fn heapify_down(&mut self, idx: usize) {
+        let left_idx = idx * 2 + 1;
+        let right_idx = idx * 2 + 2;
+        if left_idx >= self.data.len() {
+            return;
+        }
+        let min_idx =
+            if right_idx < self.data.len() && self.data[right_idx].1 < self.data[left_idx].1 {
+                right_idx
+            } else {
+                left_idx
+            };
+        if self.data[min_idx].1 < self.data[idx].1 {
+            self.swap(min_idx, idx);
+            self.heapify_down(min_idx);
+        }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.data.swap(a, b);
+        *self.positions.get_mut(&self.data[a].0).unwrap() = a;
+        *self.positions.get_mut(&self.data[b].0).unwrap() = b;
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -96,10 +728,7 @@ mod tests {
 
     #[test]
     fn test_heap() {
-        let mut heap = MinHeap::<i64> {
-            length: 0,
-            data: Vec::new(),
-        };
+        let mut heap = MinHeap::<i64>::new();
         heap.insert(3);
         heap.insert(2);
         heap.insert(1);
@@ -131,10 +760,207 @@ mod tests {
     #[test]
     #[should_panic]
     fn test_empty_delete_panics() {
-        let mut heap = MinHeap::<i64> {
-            length: 0,
-            data: Vec::new(),
-        };
+        let mut heap = MinHeap::<i64>::new();
         heap.delete();
     }
+
+    #[test]
+    fn test_max_heap_of_strings() {
+        let mut heap = MaxHeap::new();
+        for word in ["pear", "apple", "quince", "fig"] {
+            heap.insert(word.to_string());
+        }
+        let mut out = Vec::new();
+        while heap.length > 0 {
+            out.push(heap.delete());
+        }
+        assert_eq!(out, vec!["quince", "pear", "fig", "apple"]);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Job {
+        name: String,
+        priority: u8,
+    }
+
+    #[test]
+    fn test_custom_orderings() {
+        let mut heap = BinaryHeap::by_key(|job: &Job| job.priority);
+        heap.insert(Job {
+            name: "b".to_string(),
+            priority: 2,
+        });
+        heap.insert(Job {
+            name: "c".to_string(),
+            priority: 3,
+        });
+        heap.insert(Job {
+            name: "a".to_string(),
+            priority: 1,
+        });
+        assert_eq!(heap.delete().name, "a");
+        assert_eq!(heap.delete().name, "b");
+
+        let mut heap = BinaryHeap::with_comparator(|a: &(i32, i32), b: &(i32, i32)| {
+            b.1.cmp(&a.1).then(a.0.cmp(&b.0))
+        });
+        for pair in [(1, 1), (2, 5), (3, 5), (4, 0)] {
+            heap.insert(pair);
+        }
+        assert_eq!(heap.delete(), (2, 5));
+        assert_eq!(heap.delete(), (3, 5));
+        assert_eq!(heap.delete(), (1, 1));
+        assert_eq!(heap.delete(), (4, 0));
+    }
+
+    #[test]
+    fn test_indexed_min_heap() {
+        let mut heap = IndexedMinHeap::new();
+        for (key, priority) in [("a", 5), ("b", 3), ("c", 8), ("d", 1), ("e", 9)] {
+            assert!(heap.push(key, priority));
+        }
+        assert!(!heap.push("a", 0));
+        assert_eq!(heap.peek(), Some((&"d", &1)));
+
+        assert!(heap.decrease_key(&"c", 0));
+        assert!(!heap.decrease_key(&"c", 4));
+        assert!(heap.increase_key(&"d", 7));
+        assert!(!heap.increase_key(&"z", 7));
+        assert_eq!(heap.remove(&"b"), Some(3));
+        assert_eq!(heap.remove(&"b"), None);
+        assert!(!heap.contains(&"b"));
+        assert_eq!(heap.priority(&"d"), Some(&7));
+
+        let mut out = Vec::new();
+        while let Some(entry) = heap.pop() {
+            out.push(entry);
+        }
+        assert_eq!(out, vec![("c", 0), ("a", 5), ("d", 7), ("e", 9)]);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let heap = MinHeap::from_vec(vec![9, 4, 7, 1, 8, 2, 6, 3, 5, 0]);
+        assert_eq!(heap.len(), 10);
+        assert_eq!(heap.peek(), Some(&0));
+        for idx in 1..heap.len() {
+            assert!(heap.data[(idx - 1) / 2] <= heap.data[idx]);
+        }
+        assert_eq!(heap.into_sorted_vec(), (0..10).collect::<Vec<_>>());
+
+        let heap = MaxHeap::from_vec(vec!["b", "c", "a"]);
+        assert_eq!(heap.into_sorted_vec(), vec!["c", "b", "a"]);
+
+        let mut heap = MinHeap::<i64>::from_vec(Vec::new());
+        assert!(heap.is_empty());
+        assert_eq!(heap.peek(), None);
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    fn test_iterators() {
+        let heap = MaxHeap::from_vec(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+        assert_eq!(
+            heap.into_iter().collect::<Vec<_>>(),
+            vec![9, 6, 5, 4, 3, 2, 1, 1]
+        );
+
+        let mut heap = MinHeap::from_vec(vec![3, 1, 4, 1, 5]);
+        assert_eq!(heap.drain().take(2).collect::<Vec<_>>(), vec![1, 1]);
+        assert!(heap.is_empty());
+        assert!(heap.data.is_empty());
+        heap.insert(2);
+        assert_eq!(heap.drain().collect::<Vec<_>>(), vec![2]);
+    }
+
+    fn drain_dary<const D: usize>(mut heap: DaryHeap<u32, D>) -> Vec<u32> {
+        let mut out = Vec::new();
+        while let Some(val) = heap.pop() {
+            out.push(val);
+        }
+        out
+    }
+
+    #[test]
+    fn test_dary_heap() {
+        let values: Vec<u32> = (0..200).map(|i| (i * 7919) % 211).collect();
+        let mut sorted = values.clone();
+        sorted.sort();
+
+        let mut heap = DaryHeap::<u32, 4>::new();
+        for val in values.iter() {
+            heap.insert(*val);
+        }
+        assert_eq!(heap.len(), 200);
+        assert_eq!(heap.peek(), Some(&sorted[0]));
+        assert_eq!(drain_dary(heap), sorted);
+        assert_eq!(
+            drain_dary(DaryHeap::<u32, 2>::from_vec(values.clone())),
+            sorted
+        );
+        assert_eq!(
+            drain_dary(DaryHeap::<u32, 8>::from_vec(values.clone())),
+            sorted
+        );
+        assert!(DaryHeap::<u32, 3>::from_vec(vec![1]).pop() == Some(1));
+
+        let mut heap = DaryHeap::<_, 3, MaxOrder>::new();
+        for word in ["b", "d", "a", "c"] {
+            heap.insert(word);
+        }
+        assert_eq!(heap.pop(), Some("d"));
+        assert_eq!(heap.pop(), Some("c"));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least two children")]
+    fn test_unary_heap_panics() {
+        DaryHeap::<u32, 1>::new();
+    }
+
+    #[test]
+    fn test_min_max_heap() {
+        let mut heap = MinMaxHeap::new();
+        assert_eq!((heap.peek_min(), heap.peek_max()), (None, None));
+        assert_eq!((heap.pop_min(), heap.pop_max()), (None, None));
+
+        let values: Vec<u32> = (0..300).map(|i| (i * 7919) % 307).collect();
+        for val in values.iter() {
+            heap.push(*val);
+        }
+        let mut sorted = values.clone();
+        sorted.sort();
+        assert_eq!(heap.peek_min(), sorted.first());
+        assert_eq!(heap.peek_max(), sorted.last());
+
+        // Alternate ends so both trickle-down paths get exercised.
+        let (mut low, mut high) = (0, sorted.len());
+        while !heap.is_empty() {
+            if heap.len() % 3 == 0 {
+                high -= 1;
+                assert_eq!(heap.pop_max(), Some(sorted[high]));
+            } else {
+                assert_eq!(heap.pop_min(), Some(sorted[low]));
+                low += 1;
+            }
+        }
+        assert_eq!(low, high);
+    }
+
+    #[test]
+    fn test_min_max_heap_as_bounded_buffer() {
+        let mut buffer = MinMaxHeap::new();
+        for val in [5, 1, 9, 3, 7, 2, 8] {
+            buffer.push(val);
+            if buffer.len() > 4 {
+                buffer.pop_min();
+            }
+        }
+        assert_eq!(buffer.pop_max(), Some(9));
+        assert_eq!(buffer.pop_max(), Some(8));
+        assert_eq!(buffer.pop_max(), Some(7));
+        assert_eq!(buffer.pop_max(), Some(5));
+        assert_eq!(buffer.pop_max(), None);
+    }
 }
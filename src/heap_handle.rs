@@ -0,0 +1,79 @@
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+/// Tells heaps apart, so a heap can turn away a handle that came from
+/// another one. Melding a heap forwards its id to the heap it went into.
+#[derive(Default)]
+pub(crate) struct HeapId {
+    forward: RefCell<Option<Rc<HeapId>>>,
+}
+
+impl HeapId {
+    pub(crate) fn new() -> Rc<Self> {
+        Rc::default()
+    }
+
+    /// Sends this heap's handles on to `into`, once this heap's items have
+    /// been melded there.
+    pub(crate) fn forward(&self, into: &Rc<HeapId>) {
+        *self.forward.borrow_mut() = Some(into.clone());
+    }
+
+    /// The id of the heap holding this one's items now. Points every id on
+    /// the way straight at it, like path compression in a union-find.
+    fn resolve(self: &Rc<Self>) -> Rc<HeapId> {
+        let mut root = self.clone();
+        loop {
+            let next = root.forward.borrow().clone();
+            match next {
+                Some(next) => root = next,
+                None => break,
+            }
+        }
+        let mut curr = self.clone();
+        while !Rc::ptr_eq(&curr, &root) {
+            curr = curr.forward.replace(Some(root.clone())).unwrap();
+        }
+        root
+    }
+}
+
+/// Points at an item inserted into a `PairingHeap` or `FibonacciHeap`, for
+/// `decrease_key`. A handle stops working once its item has been popped,
+/// and is turned away by any heap but the one its item is in.
+pub struct Handle<N> {
+    node: Weak<RefCell<N>>,
+    heap: Rc<HeapId>,
+}
+
+impl<N> Clone for Handle<N> {
+    fn clone(&self) -> Self {
+        Self {
+            node: self.node.clone(),
+            heap: self.heap.clone(),
+        }
+    }
+}
+
+impl<N> Handle<N> {
+    pub(crate) fn new(node: &Rc<RefCell<N>>, heap: &Rc<HeapId>) -> Self {
+        Self {
+            node: Rc::downgrade(node),
+            heap: heap.clone(),
+        }
+    }
+
+    /// The handle's node, if its item is still queued in the heap `heap`.
+    pub(crate) fn node_in(&self, heap: &Rc<HeapId>) -> Option<Rc<RefCell<N>>> {
+        if !Rc::ptr_eq(&self.heap.resolve(), heap) {
+            return None;
+        }
+        self.node.upgrade()
+    }
+}
+
+/// Unwraps a node the heap has just unlinked. Handles only hold weak
+/// references, so nothing else can be keeping it alive.
+pub(crate) fn into_node<N>(node: Rc<RefCell<N>>) -> N {
+    Rc::try_unwrap(node).ok().unwrap().into_inner()
+}
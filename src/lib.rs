@@ -1,8 +1,17 @@
 pub mod arrays;
+pub mod binomial_heap;
+pub mod cache;
+pub mod fibonacci_heap;
 pub mod graphs;
 pub mod heap;
+pub mod heap_handle;
 pub mod linked_lists;
 pub mod lru;
 pub mod map;
+pub mod pairing_heap;
+pub mod persistent_heap;
+pub mod radix_heap;
 pub mod recursion;
+pub mod scheduler;
+pub mod streaming;
 pub mod trees;
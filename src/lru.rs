@@ -1,164 +1,461 @@
-use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt;
 use std::hash::Hash;
-use std::rc::Rc;
-use uuid::Uuid;
-
-// type IntMutNode<T> = Rc<RefCell<Node<T>>>;
-//
-// struct Node<T> {
-//     value: T,
-//     next: Option<IntMutNode<T>>,
-//     prev: Option<IntMutNode<T>>,
-//     uuid: Uuid,
-// }
-//
-// struct LRU<K, V>
-// where
-//     K: Hash,
-// {
-//     head: Option<IntMutNode<V>>,
-//     tail: Option<IntMutNode<V>>,
-//     lookup: HashMap<K, IntMutNode<V>>,
-//     reverse_lookup: HashMap<Uuid, K>,
-//     capacity: i64,
-//     length: i64,
-// }
-//
-// impl<K, V> LRU<K, V>
-// where
-//     K: Hash,
-//     K: Eq,
-//     V: Clone
-// {
-//     fn new(capacity: i64) -> Self {
-//         Self {
-//             head: None,
-//             tail: None,
-//             lookup: HashMap::new(),
-//             reverse_lookup: HashMap::new(),
-//             capacity,
-//             length: 0,
-//         }
-//     }
-//
-//     fn get(&mut self, key: &K) -> Option<V> {
-//         if self.lookup.contains_key(key) {
-//             let node = self.lookup[key].clone();
-//             self.detach(&node);
-//             self.prepend(&node);
-//             return Some(node.borrow().value.clone())
-//         } else {
-//             None
-//         }
-//     }
-//
-//     fn detach(&mut self, node: &IntMutNode<V>) {}
-//
-//     fn prepend(&mut self, node: &IntMutNode<V>) {}
-// }
-//
-struct Node {
-    value: i64,
-    next: Option<Rc<RefCell<Node>>>,
-    prev: Option<Rc<RefCell<Node>>>,
-    uuid: Uuid,
-}
-
-struct LRU {
-    pub head: Option<Rc<RefCell<Node>>>,
-    pub tail: Option<Rc<RefCell<Node>>>,
-    lookup: HashMap<i64, Rc<RefCell<Node>>>,
-    reverse_lookup: HashMap<String, i64>,
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+/// Why an entry left the cache, passed to the eviction listener.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionCause {
+    /// The cache grew past its capacity and dropped its least recently used entry.
+    Capacity,
+    /// The entry outlived its time to live. The cache does not expire entries on its own yet.
+    Expired,
+    /// The entry was removed with `remove`.
+    Explicit,
+    /// A `put` on an existing key overwrote the entry's value.
+    Replaced,
+}
+
+/// A snapshot of the counters collected once `record_stats` is switched on.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub inserts: u64,
+    pub updates: u64,
+    pub evictions: u64,
+    pub load_time: Duration,
+}
+
+impl CacheStats {
+    pub fn requests(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    /// Fraction of lookups that found their key, or 0 before any lookup.
+    pub fn hit_rate(&self) -> f64 {
+        if self.requests() == 0 {
+            return 0.;
+        }
+        self.hits as f64 / self.requests() as f64
+    }
+}
+
+type EvictionListener<K, V> = Box<dyn FnMut(&K, &V, EvictionCause) + Send>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+/// A least recently used cache. Entries live in a slab and link to each
+/// other by index, most recently used at `head`, so the cache owns every
+/// entry outright and is `Send` whenever its keys and values are.
+pub struct LRU<K, V> {
+    nodes: Vec<Node<K, V>>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    lookup: HashMap<K, usize>,
     capacity: i64,
-    length: i64,
+    listener: Option<EvictionListener<K, V>>,
+    stats: Option<CacheStats>,
 }
 
-impl LRU {
-    fn new(capacity: i64) -> Self {
+impl<K, V> LRU<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new(capacity: i64) -> Self {
         Self {
+            nodes: Vec::new(),
             head: None,
             tail: None,
             lookup: HashMap::new(),
-            reverse_lookup: HashMap::new(),
             capacity,
-            length: 0,
+            listener: None,
+            stats: None,
+        }
+    }
+
+    /// Registers a callback that receives every entry leaving the cache.
+    ///
+    /// The listener is only called once the cache is back in a consistent
+    /// state, and it is owned by the cache, so it can't hold a mutable
+    /// borrow of it. A listener that reaches the cache through shared
+    /// ownership (e.g. `Arc<Mutex<LRU>>`) blocks or fails to lock instead
+    /// of re-entering it mid-update.
+    pub fn on_evict<F>(&mut self, listener: F)
+    where
+        F: FnMut(&K, &V, EvictionCause) + Send + 'static,
+    {
+        self.listener = Some(Box::new(listener));
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity.max(0) as usize
+    }
+
+    /// Starts counting hits, misses, writes and evictions. Counting is off
+    /// by default so caches that don't need it pay nothing for it.
+    pub fn record_stats(&mut self) {
+        if self.stats.is_none() {
+            self.stats = Some(CacheStats::default());
         }
     }
 
-    fn get(&mut self, key: i64) -> Option<i64> {
-        if self.lookup.contains_key(&key) {
-            let node = self.lookup[&key].clone();
-            self.detach(node.clone());
-            self.prepend(node.clone());
-            return Some(node.borrow().value);
+    pub fn stats(&self) -> Option<CacheStats> {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        if let Some(stats) = self.stats.as_mut() {
+            *stats = CacheStats::default();
+        }
+    }
+
+    /// Adds time spent producing a value on a miss, for callers that load
+    /// from a backing store.
+    pub fn record_load(&mut self, elapsed: Duration) {
+        self.record(|stats| stats.load_time += elapsed);
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        if let Some(idx) = self.lookup.get(key).copied() {
+            self.record(|stats| stats.hits += 1);
+            self.detach(idx);
+            self.prepend(idx);
+            Some(self.nodes[idx].value.clone())
         } else {
+            self.record(|stats| stats.misses += 1);
             None
         }
     }
 
-    fn put(&mut self, key: i64, value: i64) {
-        if self.lookup.contains_key(&key) {
-            let node = self.lookup[&key].clone();
-            self.detach(node.clone());
-            self.prepend(node);
+    pub fn put(&mut self, key: K, value: V) {
+        if let Some(idx) = self.lookup.get(&key).copied() {
+            let old_value = std::mem::replace(&mut self.nodes[idx].value, value);
+            self.detach(idx);
+            self.prepend(idx);
+            self.record(|stats| stats.updates += 1);
+            self.notify(&key, &old_value, EvictionCause::Replaced);
         } else {
-            let uuid = Uuid::new_v4();
-            let node = Rc::new(RefCell::new(Node {
+            let idx = self.nodes.len();
+            self.nodes.push(Node {
+                key: key.clone(),
                 value,
                 prev: None,
                 next: None,
-                uuid: uuid.clone(),
-            }));
-            self.lookup.insert(key, node.clone());
-            self.reverse_lookup.insert(uuid.to_string(), key);
-            self.prepend(node);
+            });
+            self.lookup.insert(key, idx);
+            self.prepend(idx);
+            self.record(|stats| stats.inserts += 1);
+            self.trim_cache();
         }
-        self.length += 1;
-        self.trim_cache();
     }
 
-    fn detach(&mut self, node: Rc<RefCell<Node>>) {
-        if Rc::ptr_eq(&node, &self.head.clone().unwrap()) {
-            self.head = node.borrow().next.clone();
-        }
-        if Rc::ptr_eq(&node, &self.tail.clone().unwrap()) {
-            self.tail = node.borrow().prev.clone();
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let idx = self.lookup.remove(key)?;
+        let node = self.take(idx);
+        self.notify(&node.key, &node.value, EvictionCause::Explicit);
+        Some(node.value)
+    }
+
+    fn detach(&mut self, idx: usize) {
+        let (prev, next) = (self.nodes[idx].prev.take(), self.nodes[idx].next.take());
+        match prev {
+            Some(prev) => self.nodes[prev].next = next,
+            None => self.head = next,
         }
-        let mut node = node.borrow_mut();
-        if let Some(prev) = node.prev.clone() {
-            prev.borrow_mut().next = node.next.clone();
+        match next {
+            Some(next) => self.nodes[next].prev = prev,
+            None => self.tail = prev,
         }
-        if let Some(next) = node.next.clone() {
-            next.borrow_mut().prev = node.prev.clone();
+    }
+
+    fn prepend(&mut self, idx: usize) {
+        self.nodes[idx].next = self.head;
+        match self.head {
+            Some(head) => self.nodes[head].prev = Some(idx),
+            None => self.tail = Some(idx),
         }
-        node.next = None;
-        node.prev = None;
+        self.head = Some(idx);
     }
 
-    fn prepend(&mut self, node: Rc<RefCell<Node>>) {
-        if let Some(head) = self.head.clone() {
-            head.borrow_mut().prev = Some(node.clone());
-            node.borrow_mut().next = Some(head);
-            node.borrow_mut().prev = None;
-            self.head = Some(node);
-        } else {
-            self.head = Some(node.clone());
-            self.tail = Some(node.clone());
+    /// Unlinks the node at `idx` and removes it from the slab. The last
+    /// node moves into its slot, so its neighbours and lookup entry are
+    /// pointed at the new index. The caller removes the node's own lookup
+    /// entry.
+    fn take(&mut self, idx: usize) -> Node<K, V> {
+        self.detach(idx);
+        let node = self.nodes.swap_remove(idx);
+        if idx < self.nodes.len() {
+            let (prev, next) = (self.nodes[idx].prev, self.nodes[idx].next);
+            match prev {
+                Some(prev) => self.nodes[prev].next = Some(idx),
+                None => self.head = Some(idx),
+            }
+            match next {
+                Some(next) => self.nodes[next].prev = Some(idx),
+                None => self.tail = Some(idx),
+            }
+            *self.lookup.get_mut(&self.nodes[idx].key).unwrap() = idx;
         }
+        node
     }
 
     fn trim_cache(&mut self) {
-        if self.length > self.capacity {
-            let node = self.tail.clone().unwrap();
-            self.tail = node.borrow().prev.clone();
-            self.detach(node.clone());
-            let uuid = &node.clone().borrow().uuid.to_string();
-            let key = self.reverse_lookup[uuid];
-            self.lookup.remove(&key);
-            self.reverse_lookup.remove(uuid);
-            self.length -= 1;
+        if self.nodes.len() as i64 > self.capacity {
+            let node = self.take(self.tail.unwrap());
+            self.lookup.remove(&node.key);
+            self.record(|stats| stats.evictions += 1);
+            self.notify(&node.key, &node.value, EvictionCause::Capacity);
+        }
+    }
+
+    fn record(&mut self, update: impl FnOnce(&mut CacheStats)) {
+        if let Some(stats) = self.stats.as_mut() {
+            update(stats);
+        }
+    }
+
+    fn notify(&mut self, key: &K, value: &V, cause: EvictionCause) {
+        if let Some(listener) = self.listener.as_mut() {
+            listener(key, value, cause);
+        }
+    }
+}
+
+const MAGIC: &[u8; 4] = b"LRUC";
+const FORMAT_VERSION: u16 = 1;
+
+/// Converts keys and values to and from the bytes written by `LRU::save_to`.
+pub trait Persist: Sized {
+    fn to_bytes(&self) -> Vec<u8>;
+    /// Returns `None` when `bytes` isn't a valid encoding of `Self`.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+macro_rules! persist_int {
+    ($($t:ty),*) => {$(
+        impl Persist for $t {
+            fn to_bytes(&self) -> Vec<u8> {
+                self.to_le_bytes().to_vec()
+            }
+
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                Some(<$t>::from_le_bytes(bytes.try_into().ok()?))
+            }
+        }
+    )*};
+}
+
+persist_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl Persist for String {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+impl Persist for Vec<u8> {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.clone()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(bytes.to_vec())
+    }
+}
+
+#[derive(Debug)]
+pub enum PersistError {
+    Io(io::Error),
+    /// The input doesn't start with the snapshot magic bytes.
+    NotASnapshot,
+    UnsupportedVersion(u16),
+    /// The input ended before the snapshot did.
+    Truncated,
+    ChecksumMismatch {
+        expected: u32,
+        actual: u32,
+    },
+    /// A key or value couldn't be decoded, or the header is inconsistent.
+    InvalidEntry,
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistError::Io(err) => write!(f, "i/o error: {}", err),
+            PersistError::NotASnapshot => write!(f, "not an LRU snapshot"),
+            PersistError::UnsupportedVersion(version) => {
+                write!(f, "unsupported snapshot version {}", version)
+            }
+            PersistError::Truncated => write!(f, "snapshot is truncated"),
+            PersistError::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "snapshot checksum mismatch: expected {:08x}, got {:08x}",
+                expected, actual
+            ),
+            PersistError::InvalidEntry => write!(f, "snapshot contains an invalid entry"),
+        }
+    }
+}
+
+impl std::error::Error for PersistError {}
+
+impl From<io::Error> for PersistError {
+    fn from(err: io::Error) -> Self {
+        PersistError::Io(err)
+    }
+}
+
+/// Reads the fields of a snapshot in order, failing on running out of bytes.
+struct SnapshotReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], PersistError> {
+        if self.bytes.len() < len {
+            return Err(PersistError::Truncated);
         }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], PersistError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn take_prefixed(&mut self) -> Result<&'a [u8], PersistError> {
+        let len = u32::from_le_bytes(self.take_array()?);
+        self.take(len as usize)
+    }
+}
+
+/// CRC-32 (IEEE), computed bitwise since snapshots are written rarely.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn push_prefixed(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+impl<K, V> LRU<K, V>
+where
+    K: Hash + Eq + Clone + Persist,
+    V: Clone + Persist,
+{
+    /// Writes the capacity and every entry, most recently used first.
+    ///
+    /// Layout, little endian: the magic `LRUC`, a `u16` version, the `i64`
+    /// capacity, a `u64` entry count, then each key and value as a `u32`
+    /// length followed by its bytes. A CRC-32 of everything before it ends
+    /// the snapshot.
+    pub fn save_to<W: Write>(&self, mut writer: W) -> Result<(), PersistError> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        out.extend_from_slice(&self.capacity.to_le_bytes());
+        out.extend_from_slice(&(self.nodes.len() as u64).to_le_bytes());
+
+        let mut curr = self.head;
+        while let Some(idx) = curr {
+            let node = &self.nodes[idx];
+            push_prefixed(&mut out, &node.key.to_bytes());
+            push_prefixed(&mut out, &node.value.to_bytes());
+            curr = node.next;
+        }
+
+        let checksum = crc32(&out);
+        out.extend_from_slice(&checksum.to_le_bytes());
+        writer.write_all(&out)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Rebuilds a cache written by `save_to` with the same recency order.
+    /// Listeners and stats aren't part of a snapshot and start out unset.
+    pub fn load_from<R: Read>(mut reader: R) -> Result<Self, PersistError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let mut header = SnapshotReader { bytes: &bytes };
+        let magic = header.take(MAGIC.len());
+        if !matches!(magic, Ok(magic) if magic == MAGIC) {
+            return Err(PersistError::NotASnapshot);
+        }
+        let version = u16::from_le_bytes(header.take_array()?);
+        if version != FORMAT_VERSION {
+            return Err(PersistError::UnsupportedVersion(version));
+        }
+
+        if bytes.len() < MAGIC.len() + 2 + 4 {
+            return Err(PersistError::Truncated);
+        }
+        let (body, checksum) = bytes.split_at(bytes.len() - 4);
+        let expected = u32::from_le_bytes(checksum.try_into().unwrap());
+        let actual = crc32(body);
+        if expected != actual {
+            return Err(PersistError::ChecksumMismatch { expected, actual });
+        }
+
+        let mut snapshot = SnapshotReader {
+            bytes: &body[MAGIC.len() + 2..],
+        };
+        let capacity = i64::from_le_bytes(snapshot.take_array()?);
+        let count = u64::from_le_bytes(snapshot.take_array()?);
+        if capacity < 0 || count > capacity as u64 {
+            return Err(PersistError::InvalidEntry);
+        }
+
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let key = K::from_bytes(snapshot.take_prefixed()?).ok_or(PersistError::InvalidEntry)?;
+            let value =
+                V::from_bytes(snapshot.take_prefixed()?).ok_or(PersistError::InvalidEntry)?;
+            entries.push((key, value));
+        }
+        if !snapshot.bytes.is_empty() {
+            return Err(PersistError::InvalidEntry);
+        }
+
+        let mut cache = LRU::new(capacity);
+        for (key, value) in entries.into_iter().rev() {
+            if cache.lookup.contains_key(&key) {
+                return Err(PersistError::InvalidEntry);
+            }
+            cache.put(key, value);
+        }
+        Ok(cache)
     }
 }
 
@@ -166,6 +463,20 @@ impl LRU {
 mod tests {
 
     use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Values from most to least recently used, checking the back links too.
+    fn values_in_order<K, V: Clone>(cache: &LRU<K, V>) -> Vec<V> {
+        let mut values = Vec::new();
+        let (mut prev, mut curr) = (None, cache.head);
+        while let Some(idx) = curr {
+            assert_eq!(cache.nodes[idx].prev, prev);
+            values.push(cache.nodes[idx].value.clone());
+            (prev, curr) = (curr, cache.nodes[idx].next);
+        }
+        assert_eq!(cache.tail, prev);
+        values
+    }
 
     #[test]
     fn test_put() {
@@ -174,7 +485,7 @@ mod tests {
         cache.put(2, 2);
         cache.put(3, 3);
         cache.put(4, 4);
-        assert_eq!(cache.head.unwrap().borrow().value, 4);
+        assert_eq!(values_in_order(&cache)[0], 4);
     }
 
     #[test]
@@ -184,8 +495,8 @@ mod tests {
         cache.put(2, 2);
         cache.put(3, 3);
         cache.put(4, 4);
-        assert_eq!(cache.get(1).unwrap(), 1);
-        assert_eq!(cache.head.unwrap().borrow().value, 1);
+        assert_eq!(cache.get(&1).unwrap(), 1);
+        assert_eq!(values_in_order(&cache)[0], 1);
     }
 
     #[test]
@@ -198,7 +509,7 @@ mod tests {
         cache.put(5, 5);
         cache.put(6, 6);
         cache.put(7, 7);
-        assert_eq!(cache.tail.unwrap().borrow().value, 5);
+        assert_eq!(values_in_order(&cache), vec![7, 6, 5]);
     }
 
     #[test]
@@ -209,16 +520,191 @@ mod tests {
         cache.put(3, 3);
         cache.put(4, 4);
         cache.put(5, 5);
-        cache.get(3);
-        cache.get(5);
-        cache.get(2);
-        let order = [2, 5, 3, 4, 1];
-        let mut head = cache.head.clone().unwrap();
-        for key in order {
-            assert_eq!(head.borrow().value, key);
-            if key != 1 {
-                head = head.clone().borrow().next.clone().unwrap();
+        cache.get(&3);
+        cache.get(&5);
+        cache.get(&2);
+        assert_eq!(values_in_order(&cache), vec![2, 5, 3, 4, 1]);
+    }
+
+    #[test]
+    fn test_on_evict() {
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let mut cache = LRU::new(2);
+        let log = evicted.clone();
+        cache.on_evict(move |key, value, cause| log.lock().unwrap().push((*key, *value, cause)));
+
+        cache.put(1, 10);
+        cache.put(2, 20);
+        cache.put(1, 11);
+        cache.put(3, 30);
+        assert_eq!(cache.remove(&3), Some(30));
+        assert_eq!(cache.remove(&3), None);
+
+        assert_eq!(
+            *evicted.lock().unwrap(),
+            vec![
+                (1, 10, EvictionCause::Replaced),
+                (2, 20, EvictionCause::Capacity),
+                (3, 30, EvictionCause::Explicit),
+            ]
+        );
+        assert_eq!(cache.get(&1), Some(11));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_zero_capacity_evicts_immediately() {
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let mut cache = LRU::new(0);
+        let log = evicted.clone();
+        cache.on_evict(move |key, _, cause| log.lock().unwrap().push((*key, cause)));
+        cache.put(1, 1);
+        assert_eq!(cache.get(&1), None);
+        assert!(cache.head.is_none() && cache.tail.is_none());
+        assert_eq!(*evicted.lock().unwrap(), vec![(1, EvictionCause::Capacity)]);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut cache = LRU::new(2);
+        cache.put(1, 1);
+        assert_eq!(cache.stats(), None);
+
+        cache.record_stats();
+        cache.put(2, 2);
+        cache.put(2, 3);
+        cache.put(3, 3);
+        cache.get(&3);
+        cache.get(&2);
+        cache.get(&1);
+        cache.record_load(Duration::from_millis(5));
+
+        let stats = cache.stats().unwrap();
+        assert_eq!(stats.inserts, 2);
+        assert_eq!(stats.updates, 1);
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.load_time, Duration::from_millis(5));
+        assert!((stats.hit_rate() - 2. / 3.).abs() < f64::EPSILON);
+
+        cache.reset_stats();
+        assert_eq!(cache.stats(), Some(CacheStats::default()));
+        assert_eq!(cache.stats().unwrap().hit_rate(), 0.);
+    }
+
+    fn keys_in_order(cache: &LRU<String, u32>) -> Vec<String> {
+        let mut keys = Vec::new();
+        let mut curr = cache.head;
+        while let Some(idx) = curr {
+            keys.push(cache.nodes[idx].key.clone());
+            curr = cache.nodes[idx].next;
+        }
+        keys
+    }
+
+    fn snapshot() -> Vec<u8> {
+        let mut cache = LRU::new(3);
+        cache.put("a".to_string(), 1u32);
+        cache.put("b".to_string(), 2);
+        cache.put("c".to_string(), 3);
+        cache.get(&"a".to_string());
+        let mut bytes = Vec::new();
+        cache.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let bytes = snapshot();
+        let mut restored = LRU::<String, u32>::load_from(bytes.as_slice()).unwrap();
+        assert_eq!(keys_in_order(&restored), vec!["a", "c", "b"]);
+        assert_eq!(restored.capacity, 3);
+        assert_eq!(restored.len(), 3);
+
+        restored.put("d".to_string(), 4);
+        assert_eq!(restored.get(&"b".to_string()), None);
+        assert_eq!(restored.get(&"a".to_string()), Some(1));
+
+        let mut empty = Vec::new();
+        LRU::<i64, i64>::new(4).save_to(&mut empty).unwrap();
+        let restored = LRU::<i64, i64>::load_from(empty.as_slice()).unwrap();
+        assert!(restored.head.is_none());
+        assert_eq!(restored.capacity, 4);
+    }
+
+    #[test]
+    fn test_load_rejects_bad_snapshots() {
+        let bytes = snapshot();
+
+        let result = LRU::<String, u32>::load_from(&b"not a snapshot"[..]);
+        assert!(matches!(result, Err(PersistError::NotASnapshot)));
+
+        let mut corrupt = bytes.clone();
+        corrupt[20] ^= 0xff;
+        let result = LRU::<String, u32>::load_from(corrupt.as_slice());
+        assert!(matches!(result, Err(PersistError::ChecksumMismatch { .. })));
+
+        for len in 0..bytes.len() {
+            assert!(LRU::<String, u32>::load_from(&bytes[..len]).is_err());
+        }
+
+        let mut future = bytes.clone();
+        future[4] = 2;
+        let result = LRU::<String, u32>::load_from(future.as_slice());
+        assert!(matches!(result, Err(PersistError::UnsupportedVersion(2))));
+
+        let result = LRU::<String, u64>::load_from(bytes.as_slice());
+        assert!(matches!(result, Err(PersistError::InvalidEntry)));
+    }
+
+    #[test]
+    fn test_remove_keeps_links_consistent() {
+        let mut cache = LRU::new(5);
+        for key in 1..=5 {
+            cache.put(key, key * 10);
+        }
+        assert_eq!(cache.remove(&1), Some(10));
+        assert_eq!(cache.remove(&4), Some(40));
+        assert_eq!(values_in_order(&cache), vec![50, 30, 20]);
+        cache.put(6, 60);
+        cache.get(&2);
+        assert_eq!(values_in_order(&cache), vec![20, 60, 50, 30]);
+        for key in [2, 3, 5, 6] {
+            assert!(cache.remove(&key).is_some());
+        }
+        assert!(cache.is_empty());
+        assert!(cache.head.is_none() && cache.tail.is_none());
+    }
+
+    #[test]
+    fn test_drop_releases_values() {
+        struct Counted(Arc<Mutex<usize>>);
+
+        impl Clone for Counted {
+            fn clone(&self) -> Self {
+                Counted(self.0.clone())
             }
         }
+
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                *self.0.lock().unwrap() += 1;
+            }
+        }
+
+        let drops = Arc::new(Mutex::new(0));
+        let mut cache = LRU::new(3);
+        for key in 0..3 {
+            cache.put(key, Counted(drops.clone()));
+        }
+        drop(cache);
+        assert_eq!(*drops.lock().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_is_send() {
+        fn assert_send<T: Send>(_: &T) {}
+        assert_send(&LRU::<String, Vec<u8>>::new(1));
     }
 }
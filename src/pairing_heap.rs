@@ -0,0 +1,269 @@
+use crate::heap_handle::{self, HeapId};
+use std::cell::{Ref, RefCell};
+use std::rc::{Rc, Weak};
+
+type Link<T> = Rc<RefCell<PairNode<T>>>;
+
+/// A node of a `PairingHeap`. Only reachable through a `Handle`.
+pub struct PairNode<T> {
+    value: T,
+    child: Option<Link<T>>,
+    next: Option<Link<T>>,
+    // The parent for a first child, otherwise the previous sibling.
+    prev: Option<Weak<RefCell<PairNode<T>>>>,
+}
+
+pub type Handle<T> = heap_handle::Handle<PairNode<T>>;
+
+/// A min-heap stored as a tree of nodes, so two heaps meld in O(1) by
+/// hanging one root under the other. `pop` is O(log n) amortized.
+pub struct PairingHeap<T> {
+    root: Option<Link<T>>,
+    length: usize,
+    id: Rc<HeapId>,
+}
+
+impl<T: PartialOrd> Default for PairingHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialOrd> PairingHeap<T> {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            length: 0,
+            id: HeapId::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn peek(&self) -> Option<Ref<'_, T>> {
+        self.root
+            .as_ref()
+            .map(|root| Ref::map(root.borrow(), |node| &node.value))
+    }
+
+    pub fn insert(&mut self, value: T) -> Handle<T> {
+        let node = Rc::new(RefCell::new(PairNode {
+            value,
+            child: None,
+            next: None,
+            prev: None,
+        }));
+        let handle = Handle::new(&node, &self.id);
+        self.root = Some(match self.root.take() {
+            Some(root) => link(root, node),
+            None => node,
+        });
+        self.length += 1;
+        handle
+    }
+
+    /// Takes in every item of `other` in O(1) by linking the two roots.
+    /// Handles from `other` then belong to this heap.
+    pub fn meld(&mut self, mut other: PairingHeap<T>) {
+        other.id.forward(&self.id);
+        self.length += other.length;
+        other.length = 0;
+        self.root = match (self.root.take(), other.root.take()) {
+            (Some(a), Some(b)) => Some(link(a, b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let root = self.root.take()?;
+        let mut children = Vec::new();
+        let mut curr = root.borrow_mut().child.take();
+        while let Some(node) = curr {
+            curr = node.borrow_mut().next.take();
+            node.borrow_mut().prev = None;
+            children.push(node);
+        }
+        self.root = merge_pairs(children);
+        self.length -= 1;
+        Some(heap_handle::into_node(root).value)
+    }
+
+    /// Lowers the item behind `handle` to `value` and links its subtree
+    /// back in at the root. Returns `false`, changing nothing, if the
+    /// handle is from another heap, its item was popped, or `value` would
+    /// raise it.
+    pub fn decrease_key(&mut self, handle: &Handle<T>, value: T) -> bool {
+        let Some(node) = handle.node_in(&self.id) else {
+            return false;
+        };
+        if value > node.borrow().value {
+            return false;
+        }
+        node.borrow_mut().value = value;
+        if matches!(&self.root, Some(root) if Rc::ptr_eq(root, &node)) {
+            return true;
+        }
+
+        // Cut the node's subtree out of its sibling list and meld it back in
+        // at the root.
+        let prev = node.borrow_mut().prev.take();
+        let prev = prev.and_then(|prev| prev.upgrade());
+        let next = node.borrow_mut().next.take();
+        if let Some(next) = next.as_ref() {
+            next.borrow_mut().prev = prev.as_ref().map(Rc::downgrade);
+        }
+        if let Some(prev) = prev {
+            let mut prev = prev.borrow_mut();
+            if matches!(&prev.child, Some(child) if Rc::ptr_eq(child, &node)) {
+                prev.child = next;
+            } else {
+                prev.next = next;
+            }
+        }
+        let root = self.root.take().unwrap();
+        self.root = Some(link(root, node));
+        true
+    }
+}
+
+impl<T> Drop for PairingHeap<T> {
+    // Dropping the tree recursively could overflow the stack on a long
+    // sibling list, so take it apart one node at a time.
+    fn drop(&mut self) {
+        let mut stack: Vec<Link<T>> = self.root.take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            let mut node = node.borrow_mut();
+            stack.extend(node.child.take());
+            stack.extend(node.next.take());
+        }
+    }
+}
+
+/// Hangs the root with the larger value under the other one.
+fn link<T: PartialOrd>(a: Link<T>, b: Link<T>) -> Link<T> {
+    let (parent, child) = if b.borrow().value < a.borrow().value {
+        (b, a)
+    } else {
+        (a, b)
+    };
+    {
+        let mut parent_node = parent.borrow_mut();
+        let mut child_node = child.borrow_mut();
+        if let Some(first) = parent_node.child.take() {
+            first.borrow_mut().prev = Some(Rc::downgrade(&child));
+            child_node.next = Some(first);
+        }
+        child_node.prev = Some(Rc::downgrade(&parent));
+    }
+    parent.borrow_mut().child = Some(child);
+    parent
+}
+
+/// The two-pass pairing merge: link neighbours left to right, then fold
+/// the results together right to left.
+fn merge_pairs<T: PartialOrd>(nodes: Vec<Link<T>>) -> Option<Link<T>> {
+    let mut pairs = Vec::with_capacity(nodes.len() / 2 + 1);
+    let mut nodes = nodes.into_iter();
+    while let Some(a) = nodes.next() {
+        match nodes.next() {
+            Some(b) => pairs.push(link(a, b)),
+            None => pairs.push(a),
+        }
+    }
+    pairs.into_iter().rev().reduce(|acc, node| link(node, acc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(heap: &mut PairingHeap<i64>) -> Vec<i64> {
+        let mut out = Vec::new();
+        while let Some(val) = heap.pop() {
+            out.push(val);
+        }
+        out
+    }
+
+    #[test]
+    fn test_insert_pop() {
+        let mut heap = PairingHeap::new();
+        for val in [5, 3, 8, 1, 9, 2, 7] {
+            heap.insert(val);
+        }
+        assert_eq!(*heap.peek().unwrap(), 1);
+        assert_eq!(heap.len(), 7);
+        assert_eq!(drain(&mut heap), vec![1, 2, 3, 5, 7, 8, 9]);
+        assert!(heap.is_empty());
+        assert!(heap.peek().is_none());
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a = PairingHeap::new();
+        let mut b = PairingHeap::new();
+        for val in [4, 8, 2] {
+            a.insert(val);
+        }
+        let handle = b.insert(10);
+        for val in [1, 9] {
+            b.insert(val);
+        }
+        a.meld(b);
+        assert_eq!(a.len(), 6);
+        assert!(a.decrease_key(&handle, 0));
+        assert_eq!(drain(&mut a), vec![0, 1, 2, 4, 8, 9]);
+    }
+
+    #[test]
+    fn test_decrease_key() {
+        let mut heap = PairingHeap::new();
+        let handles: Vec<_> = (0..20).map(|val| heap.insert(val * 10)).collect();
+        assert_eq!(heap.pop(), Some(0));
+
+        assert!(heap.decrease_key(&handles[15], 5));
+        assert!(heap.decrease_key(&handles[7], 6));
+        assert!(heap.decrease_key(&handles[15], 4));
+        assert!(!heap.decrease_key(&handles[3], 100));
+        assert!(!heap.decrease_key(&handles[0], -1));
+
+        let mut expected: Vec<i64> = (1..20).map(|val| val * 10).collect();
+        expected.retain(|val| *val != 150 && *val != 70);
+        expected.splice(0..0, [4, 6]);
+        assert_eq!(drain(&mut heap), expected);
+    }
+
+    #[test]
+    fn test_foreign_handle() {
+        let mut a = PairingHeap::new();
+        let mut b = PairingHeap::new();
+        a.insert(1);
+        let from_b = b.insert(5);
+        b.insert(6);
+        assert!(!a.decrease_key(&from_b, 0));
+        assert_eq!(drain(&mut a), vec![1]);
+
+        // Melding hands b's handles over to the heap b went into.
+        let mut c = PairingHeap::new();
+        c.insert(3);
+        c.meld(b);
+        a.insert(2);
+        a.meld(c);
+        assert!(a.decrease_key(&from_b, 0));
+        assert_eq!(drain(&mut a), vec![0, 2, 3, 6]);
+    }
+
+    #[test]
+    fn test_drop_large_heap() {
+        let mut heap = PairingHeap::new();
+        for val in 0..200_000 {
+            heap.insert(val);
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use std::rc::Rc;
+
+// Both heaps here are persistent: `push`, `pop` and `merge` leave their
+// inputs untouched and return a new heap that shares every node it can with
+// them through `Rc`. Cloning a heap is O(1).
+
+struct LeftistNode<T> {
+    value: T,
+    // Length of the shortest path to a missing child. Keeping it no larger
+    // on the right than on the left keeps right spines O(log n) long.
+    rank: usize,
+    size: usize,
+    left: Option<Rc<LeftistNode<T>>>,
+    right: Option<Rc<LeftistNode<T>>>,
+}
+
+/// A persistent min-heap that merges down right spines in O(log n).
+pub struct LeftistHeap<T> {
+    root: Option<Rc<LeftistNode<T>>>,
+}
+
+impl<T> Clone for LeftistHeap<T> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+        }
+    }
+}
+
+impl<T: PartialOrd + Clone> Default for LeftistHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn rank<T>(node: &Option<Rc<LeftistNode<T>>>) -> usize {
+    node.as_ref().map_or(0, |node| node.rank)
+}
+
+fn size<T>(node: &Option<Rc<LeftistNode<T>>>) -> usize {
+    node.as_ref().map_or(0, |node| node.size)
+}
+
+fn merge_leftist<T: PartialOrd + Clone>(
+    a: &Option<Rc<LeftistNode<T>>>,
+    b: &Option<Rc<LeftistNode<T>>>,
+) -> Option<Rc<LeftistNode<T>>> {
+    let (a, b) = match (a, b) {
+        (None, other) | (other, None) => return other.clone(),
+        (Some(a), Some(b)) if b.value < a.value => (b, a),
+        (Some(a), Some(b)) => (a, b),
+    };
+    let merged = merge_leftist(&a.right, &Some(b.clone()));
+    let (left, right) = if rank(&a.left) >= rank(&merged) {
+        (a.left.clone(), merged)
+    } else {
+        (merged, a.left.clone())
+    };
+    Some(Rc::new(LeftistNode {
+        value: a.value.clone(),
+        rank: rank(&right) + 1,
+        size: size(&left) + size(&right) + 1,
+        left,
+        right,
+    }))
+}
+
+impl<T: PartialOrd + Clone> LeftistHeap<T> {
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|root| &root.value)
+    }
+
+    #[must_use]
+    pub fn push(&self, value: T) -> Self {
+        let single = Some(Rc::new(LeftistNode {
+            value,
+            rank: 1,
+            size: 1,
+            left: None,
+            right: None,
+        }));
+        Self {
+            root: merge_leftist(&self.root, &single),
+        }
+    }
+
+    /// Returns the minimum and the heap without it.
+    #[must_use]
+    pub fn pop(&self) -> Option<(T, Self)> {
+        let root = self.root.as_ref()?;
+        let rest = Self {
+            root: merge_leftist(&root.left, &root.right),
+        };
+        Some((root.value.clone(), rest))
+    }
+
+    #[must_use]
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            root: merge_leftist(&self.root, &other.root),
+        }
+    }
+}
+
+impl<T> Drop for LeftistHeap<T> {
+    // Left spines can be O(n) long, so free uniquely owned nodes with an
+    // explicit stack instead of recursive drops.
+    fn drop(&mut self) {
+        let mut stack: Vec<_> = self.root.take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            if let Ok(node) = Rc::try_unwrap(node) {
+                stack.extend(node.left);
+                stack.extend(node.right);
+            }
+        }
+    }
+}
+
+struct SkewNode<T> {
+    value: T,
+    size: usize,
+    left: Option<Rc<SkewNode<T>>>,
+    right: Option<Rc<SkewNode<T>>>,
+}
+
+/// A persistent min-heap that, unlike `LeftistHeap`, keeps no balance
+/// information and swaps children on every merge instead. The bounds are
+/// only amortized, so a version that is reused many times can be slow.
+pub struct SkewHeap<T> {
+    root: Option<Rc<SkewNode<T>>>,
+}
+
+impl<T> Clone for SkewHeap<T> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+        }
+    }
+}
+
+impl<T: PartialOrd + Clone> Default for SkewHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Merges down the right spines, then rebuilds the path bottom-up with each
+/// node's children swapped. Right spines aren't bounded, so this loops
+/// rather than recursing.
+fn merge_skew<T: PartialOrd + Clone>(
+    a: &Option<Rc<SkewNode<T>>>,
+    b: &Option<Rc<SkewNode<T>>>,
+) -> Option<Rc<SkewNode<T>>> {
+    let mut path = Vec::new();
+    let (mut a, mut b) = (a.clone(), b.clone());
+    let mut rest = loop {
+        match (a, b) {
+            (None, other) | (other, None) => break other,
+            (Some(x), Some(y)) => {
+                let (min, other) = if y.value < x.value { (y, x) } else { (x, y) };
+                a = min.right.clone();
+                b = Some(other);
+                path.push(min);
+            }
+        }
+    };
+    while let Some(node) = path.pop() {
+        rest = Some(Rc::new(SkewNode {
+            value: node.value.clone(),
+            size: node.size + rest.as_ref().map_or(0, |rest| rest.size)
+                - node.right.as_ref().map_or(0, |right| right.size),
+            left: rest,
+            right: node.left.clone(),
+        }));
+    }
+    rest
+}
+
+impl<T: PartialOrd + Clone> SkewHeap<T> {
+    pub fn new() -> Self {
+        Self { root: None }
+    }
+
+    pub fn len(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.size)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|root| &root.value)
+    }
+
+    #[must_use]
+    pub fn push(&self, value: T) -> Self {
+        let single = Some(Rc::new(SkewNode {
+            value,
+            size: 1,
+            left: None,
+            right: None,
+        }));
+        Self {
+            root: merge_skew(&self.root, &single),
+        }
+    }
+
+    /// Returns the minimum and the heap without it.
+    #[must_use]
+    pub fn pop(&self) -> Option<(T, Self)> {
+        let root = self.root.as_ref()?;
+        let rest = Self {
+            root: merge_skew(&root.left, &root.right),
+        };
+        Some((root.value.clone(), rest))
+    }
+
+    #[must_use]
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            root: merge_skew(&self.root, &other.root),
+        }
+    }
+}
+
+impl<T> Drop for SkewHeap<T> {
+    fn drop(&mut self) {
+        let mut stack: Vec<_> = self.root.take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            if let Ok(node) = Rc::try_unwrap(node) {
+                stack.extend(node.left);
+                stack.extend(node.right);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain_leftist(mut heap: LeftistHeap<i64>) -> Vec<i64> {
+        let mut out = Vec::new();
+        while let Some((val, rest)) = heap.pop() {
+            out.push(val);
+            heap = rest;
+        }
+        out
+    }
+
+    fn drain_skew(mut heap: SkewHeap<i64>) -> Vec<i64> {
+        let mut out = Vec::new();
+        while let Some((val, rest)) = heap.pop() {
+            out.push(val);
+            heap = rest;
+        }
+        out
+    }
+
+    #[test]
+    fn test_leftist_heap_is_persistent() {
+        let empty = LeftistHeap::new();
+        let one = empty.push(5);
+        let two = one.push(3);
+        let branch_a = two.push(4);
+        let branch_b = two.push(1).push(9);
+
+        assert!(empty.is_empty());
+        assert_eq!(one.peek(), Some(&5));
+        assert_eq!(two.len(), 2);
+        assert_eq!(drain_leftist(branch_a.clone()), vec![3, 4, 5]);
+        assert_eq!(drain_leftist(branch_b.clone()), vec![1, 3, 5, 9]);
+        assert_eq!(drain_leftist(two.clone()), vec![3, 5]);
+
+        let (min, rest) = branch_b.pop().unwrap();
+        assert_eq!((min, rest.len()), (1, 3));
+        assert_eq!(branch_b.len(), 4);
+
+        let merged = branch_a.merge(&branch_b);
+        assert_eq!(merged.len(), 7);
+        assert_eq!(drain_leftist(merged), vec![1, 3, 3, 4, 5, 5, 9]);
+        assert_eq!(drain_leftist(branch_a), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_skew_heap_is_persistent() {
+        let base = [8, 2, 6, 4]
+            .iter()
+            .fold(SkewHeap::new(), |heap, val| heap.push(*val));
+        let with_one = base.push(1);
+        let (min, without_min) = base.pop().unwrap();
+
+        assert_eq!(min, 2);
+        assert_eq!(base.len(), 4);
+        assert_eq!(with_one.peek(), Some(&1));
+        assert_eq!(drain_skew(without_min), vec![4, 6, 8]);
+        assert_eq!(
+            drain_skew(with_one.merge(&base)),
+            vec![1, 2, 2, 4, 4, 6, 6, 8, 8]
+        );
+        assert_eq!(drain_skew(base), vec![2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn test_long_spines() {
+        let mut leftist = LeftistHeap::new();
+        let mut skew = SkewHeap::new();
+        for val in (0..100_000).rev() {
+            leftist = leftist.push(val);
+            skew = skew.push(val);
+        }
+        assert_eq!(leftist.len(), 100_000);
+        assert_eq!(skew.len(), 100_000);
+        let (min, rest) = skew.pop().unwrap();
+        assert_eq!((min, rest.len()), (0, 99_999));
+    }
+}
@@ -0,0 +1,162 @@
+/// Unsigned integer keys a `RadixHeap` can bucket by bit length.
+pub trait RadixKey: Copy + Ord {
+    const BITS: u32;
+
+    /// The number of the highest bit where `self` and `other` differ,
+    /// counting from 1, or 0 when they are equal.
+    fn differing_bits(self, other: Self) -> u32;
+}
+
+macro_rules! radix_key {
+    ($($t:ty),*) => {$(
+        impl RadixKey for $t {
+            const BITS: u32 = <$t>::BITS;
+
+            fn differing_bits(self, other: Self) -> u32 {
+                Self::BITS - (self ^ other).leading_zeros()
+            }
+        }
+    )*};
+}
+
+radix_key!(u8, u16, u32, u64, usize);
+
+/// A min-heap for integer keys that are never pushed below the last key
+/// popped, as in Dijkstra with non-negative integer weights.
+///
+/// Bucket `i` holds keys whose highest bit differing from the last popped
+/// key is bit `i`. Popping empties the lowest non-empty bucket into lower
+/// ones, and since a key only ever moves to lower buckets, each one is
+/// moved O(log C) times for keys up to C.
+pub struct RadixHeap<K, V> {
+    buckets: Vec<Vec<(K, V)>>,
+    last: Option<K>,
+    length: usize,
+}
+
+impl<K: RadixKey, V> Default for RadixHeap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: RadixKey, V> RadixHeap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            buckets: (0..=K::BITS).map(|_| Vec::new()).collect(),
+            last: None,
+            length: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn push(&mut self, key: K, value: V) {
+        if let Some(last) = self.last {
+            if key < last {
+                panic!("Radix heap keys must not go below the last popped key");
+            }
+        }
+        let bucket = self.bucket(key);
+        self.buckets[bucket].push((key, value));
+        self.length += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        if self.length == 0 {
+            return None;
+        }
+        if self.buckets[0].is_empty() {
+            let idx = self.buckets.iter().position(|bucket| !bucket.is_empty())?;
+            let entries = std::mem::take(&mut self.buckets[idx]);
+            self.last = entries.iter().map(|(key, _)| *key).min();
+            for (key, value) in entries {
+                let bucket = self.bucket(key);
+                self.buckets[bucket].push((key, value));
+            }
+        }
+        self.length -= 1;
+        self.buckets[0].pop()
+    }
+
+    /// Before anything is popped there is no baseline, so every key goes in
+    /// the top bucket and the first pop sets the baseline to the minimum.
+    fn bucket(&self, key: K) -> usize {
+        match self.last {
+            Some(last) => key.differing_bits(last) as usize,
+            None => K::BITS as usize,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_pop() {
+        let mut heap = RadixHeap::new();
+        let keys: Vec<u32> = (0..500).map(|i| (i * 7919) % 1009 + 100).collect();
+        for key in keys.iter() {
+            heap.push(*key, ());
+        }
+        assert_eq!(heap.len(), 500);
+
+        let mut sorted = keys.clone();
+        sorted.sort();
+        let mut popped = Vec::new();
+        while let Some((key, _)) = heap.pop() {
+            popped.push(key);
+            // Pushing the current minimum again is allowed.
+            if popped.len() == 10 {
+                heap.push(key, ());
+                sorted.insert(10, key);
+            }
+        }
+        assert_eq!(popped, sorted);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "must not go below")]
+    fn test_non_monotone_push_panics() {
+        let mut heap = RadixHeap::new();
+        heap.push(10u64, ());
+        heap.pop();
+        heap.push(9, ());
+    }
+
+    #[test]
+    fn test_integer_dijkstra() {
+        let edges: Vec<Vec<(usize, u64)>> = vec![
+            vec![(1, 7), (2, 9), (5, 14)],
+            vec![(0, 7), (2, 10), (3, 15)],
+            vec![(0, 9), (1, 10), (3, 11), (5, 2)],
+            vec![(1, 15), (2, 11), (4, 6)],
+            vec![(3, 6), (5, 9)],
+            vec![(0, 14), (2, 2), (4, 9)],
+        ];
+        let mut dists = vec![u64::MAX; edges.len()];
+        let mut heap = RadixHeap::new();
+        dists[0] = 0;
+        heap.push(0u64, 0usize);
+        while let Some((dist, vertex)) = heap.pop() {
+            if dist > dists[vertex] {
+                continue;
+            }
+            for &(to, weight) in &edges[vertex] {
+                if dist + weight < dists[to] {
+                    dists[to] = dist + weight;
+                    heap.push(dist + weight, to);
+                }
+            }
+        }
+        assert_eq!(dists, vec![0, 7, 9, 20, 20, 11]);
+    }
+}
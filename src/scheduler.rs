@@ -0,0 +1,424 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::heap::MinHeap;
+
+/// Where "now" comes from, as time since some fixed origin.
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+/// Measures time since it was created.
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that only moves when told to. Clones share the same time, so a
+/// test can keep one and hand another to a queue.
+#[derive(Clone, Default)]
+pub struct ManualClock {
+    now: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self, now: Duration) {
+        self.now.set(now);
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// Identifies a scheduled item so it can be cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle(u64);
+
+/// Items that come out once their delay has passed, kept in a min-heap by
+/// deadline. O(log n) to schedule and poll, amortized O(1) to cancel.
+pub struct DelayQueue<T, C = SystemClock> {
+    clock: C,
+    // Cancelled items stay in the heap until they reach the top or the heap
+    // is rebuilt; `items` is what is actually still scheduled.
+    heap: MinHeap<(Duration, u64)>,
+    items: HashMap<u64, T>,
+    next_id: u64,
+}
+
+impl<T> Default for DelayQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> DelayQueue<T> {
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock::new())
+    }
+}
+
+impl<T, C: Clock> DelayQueue<T, C> {
+    pub fn with_clock(clock: C) -> Self {
+        Self {
+            clock,
+            heap: MinHeap::new(),
+            items: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn schedule(&mut self, item: T, delay: Duration) -> Handle {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.heap.insert((self.clock.now() + delay, id));
+        self.items.insert(id, item);
+        Handle(id)
+    }
+
+    /// Returns the item if it was still scheduled.
+    pub fn cancel(&mut self, handle: Handle) -> Option<T> {
+        let item = self.items.remove(&handle.0)?;
+        if self.heap.len() > 2 * self.items.len() + 16 {
+            let live = std::mem::take(&mut self.heap.data)
+                .into_iter()
+                .filter(|(_, id)| self.items.contains_key(id))
+                .collect();
+            self.heap = MinHeap::from_vec(live);
+        }
+        Some(item)
+    }
+
+    /// The earliest deadline still scheduled.
+    pub fn next_deadline(&mut self) -> Option<Duration> {
+        self.skip_cancelled();
+        self.heap.peek().map(|(deadline, _)| *deadline)
+    }
+
+    /// Removes and returns every item due at or before `now`, earliest
+    /// first. Items with the same deadline come out in scheduling order.
+    pub fn poll_expired(&mut self, now: Duration) -> Vec<T> {
+        let mut expired = Vec::new();
+        while self.next_deadline().is_some_and(|deadline| deadline <= now) {
+            let (_, id) = self.heap.delete();
+            expired.extend(self.items.remove(&id));
+        }
+        expired
+    }
+
+    /// `poll_expired` at the clock's current time.
+    pub fn poll(&mut self) -> Vec<T> {
+        self.poll_expired(self.clock.now())
+    }
+
+    fn skip_cancelled(&mut self) {
+        while let Some((_, id)) = self.heap.peek() {
+            if self.items.contains_key(id) {
+                return;
+            }
+            self.heap.delete();
+        }
+    }
+}
+
+const SLOT_BITS: u32 = 6;
+const SLOTS: usize = 1 << SLOT_BITS;
+const LEVELS: usize = 6;
+
+fn slot(level: usize, tick: u64) -> usize {
+    ((tick >> (SLOT_BITS * level as u32)) as usize) & (SLOTS - 1)
+}
+
+struct WheelEntry<T> {
+    item: T,
+    deadline: u64,
+    // `None` once the entry is due and waiting in `ready`.
+    level: Option<usize>,
+}
+
+/// A hierarchical timing wheel: `LEVELS` wheels of 64 slots, where a slot
+/// on level `l` spans 64^l ticks. Scheduling and cancelling are O(1). An
+/// item starts on the level where its deadline first differs from the
+/// current tick and moves down a level each time the wheel reaches its
+/// slot, so it is moved at most `LEVELS` times before it fires.
+///
+/// Deadlines are rounded up to whole ticks, so items never fire early but
+/// may fire up to one tick late. Deadlines beyond 64^6 ticks wait in the
+/// top level until they come within range.
+pub struct TimingWheel<T, C = SystemClock> {
+    clock: C,
+    tick: Duration,
+    elapsed: u64,
+    // Slots hold ids and may still list cancelled ones; `entries` decides.
+    slots: Vec<Vec<Vec<u64>>>,
+    level_len: [usize; LEVELS],
+    ready: Vec<u64>,
+    entries: HashMap<u64, WheelEntry<T>>,
+    next_id: u64,
+}
+
+impl<T> TimingWheel<T> {
+    pub fn new(tick: Duration) -> Self {
+        Self::with_clock(tick, SystemClock::new())
+    }
+}
+
+impl<T, C: Clock> TimingWheel<T, C> {
+    /// Panics if `tick` is zero.
+    pub fn with_clock(tick: Duration, clock: C) -> Self {
+        if tick.is_zero() {
+            panic!("Timing wheel ticks must be longer than zero");
+        }
+        let elapsed = (clock.now().as_nanos() / tick.as_nanos()) as u64;
+        Self {
+            clock,
+            tick,
+            elapsed,
+            slots: (0..LEVELS).map(|_| vec![Vec::new(); SLOTS]).collect(),
+            level_len: [0; LEVELS],
+            ready: Vec::new(),
+            entries: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn schedule(&mut self, item: T, delay: Duration) -> Handle {
+        let at = (self.clock.now() + delay).as_nanos();
+        let deadline = at.div_ceil(self.tick.as_nanos()) as u64;
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.insert(
+            id,
+            WheelEntry {
+                item,
+                deadline,
+                level: None,
+            },
+        );
+        self.place(id);
+        Handle(id)
+    }
+
+    /// Returns the item if it was still scheduled.
+    pub fn cancel(&mut self, handle: Handle) -> Option<T> {
+        let entry = self.entries.remove(&handle.0)?;
+        if let Some(level) = entry.level {
+            self.level_len[level] -= 1;
+        }
+        Some(entry.item)
+    }
+
+    /// Turns the wheel up to `now` and returns every item that came due,
+    /// earliest first. Items with the same deadline tick come out in
+    /// scheduling order.
+    pub fn poll_expired(&mut self, now: Duration) -> Vec<T> {
+        let now = (now.as_nanos() / self.tick.as_nanos()) as u64;
+        while self.elapsed < now {
+            // Nothing fires or moves between multiples of 64^l while every
+            // level below `l` is empty, so jump straight to the next one.
+            let level = self.level_len.iter().position(|len| *len > 0);
+            let Some(level) = level else {
+                self.elapsed = now;
+                break;
+            };
+            let span = 1u64 << (SLOT_BITS * level as u32);
+            let next = (self.elapsed / span + 1) * span;
+            if next > now {
+                self.elapsed = now;
+                break;
+            }
+            self.elapsed = next;
+            self.turn();
+        }
+
+        let mut ready: Vec<_> = std::mem::take(&mut self.ready)
+            .into_iter()
+            .filter_map(|id| Some((id, self.entries.remove(&id)?)))
+            .collect();
+        ready.sort_by_key(|(id, entry)| (entry.deadline, *id));
+        ready.into_iter().map(|(_, entry)| entry.item).collect()
+    }
+
+    /// `poll_expired` at the clock's current time.
+    pub fn poll(&mut self) -> Vec<T> {
+        self.poll_expired(self.clock.now())
+    }
+
+    /// Handles the tick just reached: higher levels pour the slot that
+    /// starts here into lower ones, top down, then level 0 fires.
+    fn turn(&mut self) {
+        for level in (0..LEVELS).rev() {
+            let span = 1u64 << (SLOT_BITS * level as u32);
+            if !self.elapsed.is_multiple_of(span) {
+                continue;
+            }
+            let slot = slot(level, self.elapsed);
+            for id in std::mem::take(&mut self.slots[level][slot]) {
+                let Some(entry) = self.entries.get_mut(&id) else {
+                    continue;
+                };
+                entry.level = None;
+                self.level_len[level] -= 1;
+                self.place(id);
+            }
+        }
+    }
+
+    fn place(&mut self, id: u64) {
+        let entry = self.entries.get_mut(&id).unwrap();
+        if entry.deadline <= self.elapsed {
+            self.ready.push(id);
+            return;
+        }
+        let differing = u64::BITS - (entry.deadline ^ self.elapsed).leading_zeros();
+        let level = ((differing - 1) / SLOT_BITS).min(LEVELS as u32 - 1) as usize;
+        entry.level = Some(level);
+        let slot = slot(level, entry.deadline);
+        self.slots[level][slot].push(id);
+        self.level_len[level] += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_delay_queue() {
+        let clock = ManualClock::new();
+        let mut queue = DelayQueue::with_clock(clock.clone());
+        queue.schedule("c", ms(30));
+        let b = queue.schedule("b", ms(20));
+        queue.schedule("a", ms(10));
+        clock.advance(ms(5));
+        queue.schedule("d", ms(25));
+        assert_eq!(queue.len(), 4);
+        assert_eq!(queue.next_deadline(), Some(ms(10)));
+
+        assert!(queue.poll().is_empty());
+        assert_eq!(queue.cancel(b), Some("b"));
+        assert_eq!(queue.cancel(b), None);
+        assert_eq!(queue.poll_expired(ms(29)), vec!["a"]);
+        assert_eq!(queue.poll_expired(ms(30)), vec!["c", "d"]);
+        assert!(queue.is_empty());
+        assert_eq!(queue.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_delay_queue_compacts_cancelled() {
+        let mut queue = DelayQueue::with_clock(ManualClock::new());
+        let handles: Vec<_> = (0..1000).map(|i| queue.schedule(i, ms(i))).collect();
+        for handle in &handles[..990] {
+            queue.cancel(*handle);
+        }
+        assert!(queue.heap.len() < 100);
+        assert_eq!(
+            queue.poll_expired(ms(1000)),
+            (990..1000).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_timing_wheel_fires_on_time() {
+        let clock = ManualClock::new();
+        clock.set(ms(3));
+        let mut wheel = TimingWheel::with_clock(ms(1), clock.clone());
+        // Spread deadlines across several levels, some sharing a tick.
+        let delays: Vec<u64> = (0..2000).map(|i| (i * 7919) % 300_000).collect();
+        let handles: Vec<_> = delays
+            .iter()
+            .enumerate()
+            .map(|(i, delay)| wheel.schedule(i, ms(*delay)))
+            .collect();
+        let cancelled: Vec<usize> = (0..2000).step_by(7).collect();
+        for i in &cancelled {
+            assert_eq!(wheel.cancel(handles[*i]), Some(*i));
+        }
+
+        let mut now = 3;
+        let mut fired = 0;
+        while !wheel.is_empty() {
+            now += 997;
+            clock.set(ms(now));
+            let due = wheel.poll();
+            for pair in due.windows(2) {
+                let key = |i: usize| (delays[i], i);
+                assert!(key(pair[0]) < key(pair[1]));
+            }
+            for i in due {
+                assert!(!cancelled.contains(&i));
+                assert!(delays[i] + 3 <= now && now < delays[i] + 3 + 997);
+                fired += 1;
+            }
+        }
+        assert_eq!(fired, 2000 - cancelled.len());
+    }
+
+    #[test]
+    fn test_timing_wheel_edges() {
+        let clock = ManualClock::new();
+        let mut wheel = TimingWheel::with_clock(ms(10), clock.clone());
+        wheel.schedule("now", ms(0));
+        wheel.schedule("rounded up", ms(1));
+        let far = ms(10 << 40);
+        wheel.schedule("far", far);
+
+        assert_eq!(wheel.poll(), vec!["now"]);
+        assert!(wheel.poll_expired(ms(9)).is_empty());
+        assert_eq!(wheel.poll_expired(ms(10)), vec!["rounded up"]);
+        assert!(wheel.poll_expired(far - ms(1)).is_empty());
+        assert_eq!(wheel.poll_expired(far), vec!["far"]);
+        assert!(wheel.is_empty());
+    }
+}
@@ -0,0 +1,326 @@
+use crate::heap::{MaxHeap, MinHeap};
+
+/// Keeps the `k` largest items seen so far in a min-heap of size `k`, so the
+/// smallest kept item is the one to beat. O(log k) per item, O(k) memory.
+pub struct TopK<T> {
+    k: usize,
+    heap: MinHeap<T>,
+}
+
+impl<T: PartialOrd> TopK<T> {
+    pub fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: MinHeap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// The smallest item still in the top k.
+    pub fn threshold(&self) -> Option<&T> {
+        self.heap.peek()
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.heap.len() < self.k {
+            self.heap.insert(item);
+        } else if self.heap.peek().is_some_and(|smallest| item > *smallest) {
+            self.heap.delete();
+            self.heap.insert(item);
+        }
+    }
+
+    /// The kept items, largest first.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        let mut items = self.heap.into_sorted_vec();
+        items.reverse();
+        items
+    }
+}
+
+impl<T: PartialOrd> Extend<T> for TopK<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+/// Tracks the median of a stream with a max-heap of the lower half and a
+/// min-heap of the upper half. The lower half is never smaller than the
+/// upper one and never more than one item bigger, so the middle items are
+/// always at the two roots.
+pub struct RunningMedian<T> {
+    lower: MaxHeap<T>,
+    upper: MinHeap<T>,
+}
+
+impl<T: PartialOrd> Default for RunningMedian<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialOrd> RunningMedian<T> {
+    pub fn new() -> Self {
+        Self {
+            lower: MaxHeap::new(),
+            upper: MinHeap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lower.len() + self.upper.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lower.is_empty()
+    }
+
+    pub fn push(&mut self, item: T) {
+        if self.lower.peek().is_none_or(|top| item <= *top) {
+            self.lower.insert(item);
+        } else {
+            self.upper.insert(item);
+        }
+
+        if self.lower.len() > self.upper.len() + 1 {
+            self.upper.insert(self.lower.delete());
+        } else if self.upper.len() > self.lower.len() {
+            self.lower.insert(self.upper.delete());
+        }
+    }
+
+    /// The middle item for an odd count, or the two middle items for an
+    /// even one, smaller first. O(1).
+    pub fn middle(&self) -> Option<(&T, &T)> {
+        let low = self.lower.peek()?;
+        if self.lower.len() > self.upper.len() {
+            return Some((low, low));
+        }
+        Some((low, self.upper.peek()?))
+    }
+}
+
+/// Numbers `RunningMedian::median` can average. Wide integers round to
+/// the nearest `f64`, as `as` does.
+pub trait AsF64: Copy {
+    fn as_f64(self) -> f64;
+}
+
+macro_rules! as_f64 {
+    ($($t:ty),*) => {$(
+        impl AsF64 for $t {
+            fn as_f64(self) -> f64 {
+                self as f64
+            }
+        }
+    )*};
+}
+
+as_f64!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+impl<T: PartialOrd + AsF64> RunningMedian<T> {
+    /// The median, averaging the two middle items for an even count. O(1).
+    pub fn median(&self) -> Option<f64> {
+        let (low, high) = self.middle()?;
+        Some((low.as_f64() + high.as_f64()) / 2.)
+    }
+}
+
+impl<T: PartialOrd> Extend<T> for RunningMedian<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+/// Lazily merges sorted iterators into one sorted iterator, keeping one
+/// item per input in a heap. Equal items come out in the order of their
+/// inputs in `iters`, and in their original order within an input.
+pub fn merge_sorted<I>(iters: Vec<I>) -> impl Iterator<Item = I::Item>
+where
+    I: Iterator,
+    I::Item: PartialOrd,
+{
+    MergeSorted::new(iters)
+}
+
+/// Like `merge_sorted`, but yields only the first of each run of equal
+/// items.
+pub fn merge_sorted_dedup<I>(iters: Vec<I>) -> impl Iterator<Item = I::Item>
+where
+    I: Iterator,
+    I::Item: PartialOrd,
+{
+    let mut merged = MergeSorted::new(iters);
+    std::iter::from_fn(move || {
+        let item = merged.next()?;
+        while merged.heap.peek().is_some_and(|(next, _)| *next == item) {
+            merged.next();
+        }
+        Some(item)
+    })
+}
+
+struct MergeSorted<I: Iterator> {
+    iters: Vec<I>,
+    // Ties on the item fall back to the input's index, which is what makes
+    // the merge stable.
+    heap: MinHeap<(I::Item, usize)>,
+}
+
+impl<I> MergeSorted<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd,
+{
+    fn new(mut iters: Vec<I>) -> Self {
+        let mut heap = MinHeap::new();
+        for (idx, iter) in iters.iter_mut().enumerate() {
+            if let Some(item) = iter.next() {
+                heap.insert((item, idx));
+            }
+        }
+        Self { iters, heap }
+    }
+}
+
+impl<I> Iterator for MergeSorted<I>
+where
+    I: Iterator,
+    I::Item: PartialOrd,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let (item, idx) = self.heap.pop()?;
+        if let Some(next) = self.iters[idx].next() {
+            self.heap.insert((next, idx));
+        }
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_k() {
+        let mut top = TopK::new(3);
+        assert!(top.is_empty());
+        top.extend([5, 1, 9, 3, 7, 9, 2]);
+        assert_eq!(top.len(), 3);
+        assert_eq!(top.threshold(), Some(&7));
+        assert_eq!(top.into_sorted_vec(), vec![9, 9, 7]);
+
+        let mut top = TopK::new(5);
+        top.extend(["b", "a"]);
+        assert_eq!(top.into_sorted_vec(), vec!["b", "a"]);
+
+        let mut top = TopK::new(0);
+        top.push(1);
+        assert!(top.is_empty());
+    }
+
+    #[test]
+    fn test_running_median() {
+        let mut median = RunningMedian::new();
+        assert_eq!(median.median(), None);
+
+        let stream = [5, 15, 1, 3, 2, 8, 7, 9, 10, 6, 11, 4];
+        let mut seen = Vec::new();
+        for val in stream {
+            median.push(val);
+            seen.push(val);
+            seen.sort();
+            let mid = seen.len() / 2;
+            let expected = if seen.len() % 2 == 1 {
+                seen[mid] as f64
+            } else {
+                (seen[mid - 1] + seen[mid]) as f64 / 2.
+            };
+            assert_eq!(median.median(), Some(expected));
+        }
+        assert_eq!(median.len(), stream.len());
+        assert_eq!(median.middle(), Some((&6, &7)));
+
+        let mut large = RunningMedian::<u64>::new();
+        large.extend([u64::MAX, 1 << 60, 3]);
+        assert_eq!(large.median(), Some((1u64 << 60) as f64));
+        large.push(1 << 62);
+        assert_eq!(large.median(), Some(((1u64 << 60) + (1 << 62)) as f64 / 2.));
+
+        let mut words = RunningMedian::new();
+        words.extend(["pear", "fig", "apple"]);
+        assert_eq!(words.middle(), Some((&"fig", &"fig")));
+    }
+
+    #[test]
+    fn test_merge_sorted() {
+        let merged: Vec<_> = merge_sorted(vec![
+            vec![1, 4, 7, 10].into_iter(),
+            vec![].into_iter(),
+            vec![2, 4, 8].into_iter(),
+            vec![0, 4, 11, 12].into_iter(),
+        ])
+        .collect();
+        assert_eq!(merged, vec![0, 1, 2, 4, 4, 4, 7, 8, 10, 11, 12]);
+
+        // Equal keys keep the order of their inputs.
+        let shards = vec![
+            vec![(1, 'a'), (2, 'a'), (2, 'b')].into_iter(),
+            vec![(1, 'c'), (2, 'c')].into_iter(),
+        ];
+        let merged: Vec<_> = merge_sorted(
+            shards
+                .into_iter()
+                .map(|shard| shard.map(|(key, tag)| Tagged(key, tag)))
+                .collect(),
+        )
+        .map(|Tagged(key, tag)| (key, tag))
+        .collect();
+        assert_eq!(
+            merged,
+            vec![(1, 'a'), (1, 'c'), (2, 'a'), (2, 'b'), (2, 'c')]
+        );
+
+        let none: Vec<std::vec::IntoIter<i32>> = Vec::new();
+        assert_eq!(merge_sorted(none).count(), 0);
+    }
+
+    #[test]
+    fn test_merge_sorted_dedup() {
+        let merged: Vec<_> = merge_sorted_dedup(vec![
+            vec![1, 1, 3, 5].into_iter(),
+            vec![1, 2, 3].into_iter(),
+            vec![5, 5, 6].into_iter(),
+        ])
+        .collect();
+        assert_eq!(merged, vec![1, 2, 3, 5, 6]);
+    }
+
+    /// Compares on the key only, so the tag shows which input won a tie.
+    struct Tagged(i32, char);
+
+    impl PartialEq for Tagged {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl PartialOrd for Tagged {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            self.0.partial_cmp(&other.0)
+        }
+    }
+}